@@ -0,0 +1,285 @@
+use crate::cidr::Cidr;
+use crate::ipset::IpSet;
+use crate::range::{IPV4_BITS, IPV6_BITS, block_last, common_block, range_to_blocks};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AggregateError {
+    #[error("Cannot summarize into {max} prefixes (expected at least {min})")]
+    TooFewPrefixes { max: usize, min: usize },
+}
+
+/// A prefix of a lossy summary
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SummarizedCidr {
+    pub cidr: Cidr,
+    /// Addresses covered by this prefix that none of the summarized networks covered
+    pub extra_addresses: u128,
+}
+
+/// Trait that provides `aggregate` and `summarize` functions for lists of networks
+///
+/// IPv4 networks come first in the results, then IPv6, each sorted by address
+///
+/// # Example
+/// ```
+/// use cidit::{Aggregatable, Cidr};
+///
+/// let expected_cidrs: Vec<Cidr> = ["10.0.1.0/24", "10.0.0.0/24", "10.0.0.128/25", "10.0.2.0/24"]
+///     .iter()
+///     .map(|cidr| cidr.parse().unwrap())
+///     .collect();
+///
+/// let actual_aggregate: Vec<Cidr> = expected_cidrs.aggregate();
+/// let actual_summary = expected_cidrs.summarize(1).unwrap();
+///
+/// assert_eq!(actual_aggregate, vec!["10.0.0.0/23".parse().unwrap(), "10.0.2.0/24".parse().unwrap()]);
+/// assert_eq!(actual_summary[0].cidr, "10.0.0.0/22".parse().unwrap());
+/// assert_eq!(actual_summary[0].extra_addresses, 256);
+/// ```
+pub trait Aggregatable {
+    /// Minimal list of networks that covers exactly the same addresses
+    fn aggregate(&self) -> Vec<Cidr>;
+
+    /// At most `max_prefixes` networks that cover all the addresses, and possibly more
+    fn summarize(&self, max_prefixes: usize) -> Result<Vec<SummarizedCidr>, AggregateError>;
+}
+
+/// Number of addresses of sorted, disjoint `ranges` inside `first..=last`, minus one.
+/// `None` if there are none
+fn covered_minus_one(ranges: &[(u128, u128)], first: u128, last: u128) -> Option<u128> {
+    let overlapping = ranges.partition_point(|(_, end)| *end < first);
+    ranges[overlapping..]
+        .iter()
+        .take_while(|(start, _)| *start <= last)
+        .map(|(start, end)| (*end).min(last) - (*start).max(first))
+        .reduce(|total, size_minus_one| total + size_minus_one + 1)
+}
+
+/// Addresses of `first..=last` not covered by `ranges`
+fn extra_addresses(ranges: &[(u128, u128)], first: u128, last: u128) -> u128 {
+    match covered_minus_one(ranges, first, last) {
+        Some(covered) => (last - first) - covered,
+        None => last - first + 1,
+    }
+}
+
+/// Exact cover of one address family, and the blocks that currently summarize it
+struct FamilyCover {
+    bits: u8,
+    ranges: Vec<(u128, u128)>,
+    blocks: Vec<(u128, u8)>,
+}
+
+impl FamilyCover {
+    fn new(set: &IpSet, bits: u8) -> Self {
+        let ranges = set.ranges(bits).to_vec();
+        let blocks = ranges
+            .iter()
+            .flat_map(|&(start, end)| range_to_blocks(start, end, bits))
+            .collect();
+        Self {
+            bits,
+            ranges,
+            blocks,
+        }
+    }
+
+    fn last(&self, (start, prefix): (u128, u8)) -> u128 {
+        block_last(start, prefix, self.bits)
+    }
+
+    /// Common supernet of two neighbouring blocks that adds the fewest new addresses
+    fn cheapest_merge(&self) -> Option<(u128, (u128, u8))> {
+        let current: Vec<(u128, u128)> = self
+            .blocks
+            .iter()
+            .map(|&block| (block.0, self.last(block)))
+            .collect();
+        self.blocks
+            .windows(2)
+            .map(|pair| {
+                let supernet = common_block(pair[0].0, self.last(pair[1]), self.bits);
+                let cost = extra_addresses(&current, supernet.0, self.last(supernet));
+                (cost, supernet)
+            })
+            .min_by_key(|(cost, _)| *cost)
+    }
+
+    /// Replaces every block inside `supernet` with the supernet itself
+    fn merge(&mut self, supernet: (u128, u8)) {
+        let supernet_last = self.last(supernet);
+        self.blocks
+            .retain(|&(start, _)| start < supernet.0 || start > supernet_last);
+        self.blocks.push(supernet);
+        self.blocks.sort_unstable();
+    }
+}
+
+impl Aggregatable for [Cidr] {
+    fn aggregate(&self) -> Vec<Cidr> {
+        self.iter().copied().collect::<IpSet>().cidrs()
+    }
+
+    fn summarize(&self, max_prefixes: usize) -> Result<Vec<SummarizedCidr>, AggregateError> {
+        let set: IpSet = self.iter().copied().collect();
+        let mut families: Vec<FamilyCover> = [IPV4_BITS, IPV6_BITS]
+            .into_iter()
+            .map(|bits| FamilyCover::new(&set, bits))
+            .filter(|family| !family.blocks.is_empty())
+            .collect();
+        if max_prefixes < families.len() {
+            return Err(AggregateError::TooFewPrefixes {
+                max: max_prefixes,
+                min: families.len(),
+            });
+        }
+
+        while families
+            .iter()
+            .map(|family| family.blocks.len())
+            .sum::<usize>()
+            > max_prefixes
+        {
+            let (family, supernet) = families
+                .iter_mut()
+                .filter_map(|family| {
+                    family
+                        .cheapest_merge()
+                        .map(|(cost, supernet)| (cost, family, supernet))
+                })
+                .min_by_key(|(cost, _, _)| *cost)
+                .map(|(_, family, supernet)| (family, supernet))
+                .expect("a family with more than one block");
+            family.merge(supernet);
+        }
+
+        Ok(families
+            .iter()
+            .flat_map(|family| {
+                family.blocks.iter().map(|&block| SummarizedCidr {
+                    cidr: Cidr::from_block(block.0, block.1, family.bits),
+                    extra_addresses: extra_addresses(&family.ranges, block.0, family.last(block)),
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Aggregatable, AggregateError, SummarizedCidr};
+    use crate::cidr::cidrs;
+
+    #[test]
+    fn test_aggregate_adjacent_and_overlapping() {
+        // Arrange
+        let expected_cidrs = cidrs(&[
+            "10.0.0.0/23",
+            "10.0.3.0/24",
+            "2001:db8::/47",
+            "2001:db8:2::/48",
+        ]);
+
+        // Act
+        let actual_cidrs = cidrs(&[
+            "2001:db8:1::/48",
+            "10.0.1.0/24",
+            "10.0.0.77/24",
+            "10.0.3.0/24",
+            "2001:db8:2::/48",
+            "10.0.1.128/25",
+            "2001:db8::/48",
+        ])
+        .aggregate();
+
+        // Assert
+        assert_eq!(actual_cidrs, expected_cidrs);
+    }
+
+    #[test]
+    fn test_aggregate_whole_space() {
+        // Arrange
+        let expected_cidrs = cidrs(&["0.0.0.0/0"]);
+
+        // Act
+        let actual_cidrs = cidrs(&["0.0.0.0/1", "128.0.0.0/2", "192.0.0.0/2"]).aggregate();
+
+        // Assert
+        assert_eq!(actual_cidrs, expected_cidrs);
+    }
+
+    #[test]
+    fn test_summarize_reports_extra_addresses() {
+        // Arrange
+        let expected_summary = vec![
+            SummarizedCidr {
+                cidr: "10.0.0.0/22".parse().unwrap(),
+                extra_addresses: 256,
+            },
+            SummarizedCidr {
+                cidr: "192.168.0.0/24".parse().unwrap(),
+                extra_addresses: 0,
+            },
+        ];
+
+        // Act
+        let actual_summary = cidrs(&[
+            "10.0.0.0/24",
+            "10.0.1.0/24",
+            "10.0.3.0/24",
+            "192.168.0.0/24",
+        ])
+        .summarize(2)
+        .unwrap();
+
+        // Assert
+        assert_eq!(actual_summary, expected_summary);
+    }
+
+    #[test]
+    fn test_summarize_already_small_enough() {
+        // Arrange
+        let expected_cidrs = cidrs(&["10.0.0.0/24", "10.0.5.0/24"]);
+
+        // Act
+        let actual_summary = expected_cidrs.summarize(5).unwrap();
+
+        // Assert
+        assert_eq!(actual_summary.len(), 2);
+        assert!(actual_summary.iter().all(|s| s.extra_addresses == 0));
+    }
+
+    #[test]
+    fn test_summarize_mixed_families() {
+        // Arrange
+        let expected_cidrs = cidrs(&[
+            "10.0.0.0/24",
+            "10.0.2.0/24",
+            "2001:db8::/48",
+            "2001:db8:2::/48",
+        ]);
+
+        // Act
+        let actual_summary = expected_cidrs.summarize(2).unwrap();
+
+        // Assert
+        assert_eq!(actual_summary[0].cidr, "10.0.0.0/22".parse().unwrap());
+        assert_eq!(actual_summary[1].cidr, "2001:db8::/46".parse().unwrap());
+    }
+
+    #[test]
+    fn test_summarize_too_few_prefixes() {
+        // Arrange
+        let expected_cidrs = cidrs(&["10.0.0.0/24", "2001:db8::/48"]);
+
+        // Act
+        let actual_result = expected_cidrs.summarize(1);
+
+        // Assert
+        assert_eq!(
+            actual_result,
+            Err(AggregateError::TooFewPrefixes { max: 1, min: 2 })
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::cidr::Cidr;
+use crate::ipset::IpSet;
+use crate::range::{block_size_minus_one, range_to_blocks};
+use std::cmp::Reverse;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AllocateError {
+    #[error("Invalid subnet prefix: /{new} (expected /{current} to /{max})")]
+    InvalidPrefix { new: u8, current: u8, max: u8 },
+    #[error("Only {found} free /{prefix} subnets left in {pool}, {count} requested")]
+    InsufficientSpace {
+        count: usize,
+        found: usize,
+        prefix: u8,
+        pool: Cidr,
+    },
+}
+
+/// Which free space subnets are taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitStrategy {
+    /// Lowest free addresses first
+    #[default]
+    FirstFit,
+    /// Smallest free gap (contiguous free range) that holds a subnet first, so larger gaps stay open
+    BestFit,
+    /// Smallest free aligned block first, so the largest aligned blocks stay whole even inside one gap
+    KeepLarge,
+}
+
+/// Free aligned block `(start, prefix)` and the size of the gap it lies in, minus one
+struct FreeBlock {
+    start: u128,
+    prefix: u8,
+    gap: u128,
+}
+
+impl Cidr {
+    /// Next `count` free, aligned `/prefix` subnets of the network that avoid every address in `used`
+    ///
+    /// # Example
+    /// ```
+    /// use cidit::{Cidr, FitStrategy, IpSet};
+    ///
+    /// let pool: Cidr = "10.0.0.0/16".parse().unwrap();
+    /// let used: IpSet = ["10.0.0.0/24", "10.0.2.0/23"]
+    ///     .iter()
+    ///     .map(|cidr| cidr.parse::<Cidr>().unwrap())
+    ///     .collect();
+    ///
+    /// let actual_subnets = pool.allocate(&used, 24, 2, FitStrategy::FirstFit).unwrap();
+    ///
+    /// assert_eq!(actual_subnets, vec!["10.0.1.0/24".parse().unwrap(), "10.0.4.0/24".parse().unwrap()]);
+    /// ```
+    pub fn allocate(
+        &self,
+        used: &IpSet,
+        prefix: u8,
+        count: usize,
+        strategy: FitStrategy,
+    ) -> Result<Vec<Cidr>, AllocateError> {
+        let (current, bits) = (self.prefix_len(), self.bits());
+        if prefix < current || prefix > bits {
+            return Err(AllocateError::InvalidPrefix {
+                new: prefix,
+                current,
+                max: bits,
+            });
+        }
+
+        let free = IpSet::from(*self).difference(used);
+        let mut blocks: Vec<FreeBlock> = free
+            .ranges(bits)
+            .iter()
+            .flat_map(|&(start, end)| {
+                range_to_blocks(start, end, bits).into_iter().map(
+                    move |(block_start, block_prefix)| FreeBlock {
+                        start: block_start,
+                        prefix: block_prefix,
+                        gap: end - start,
+                    },
+                )
+            })
+            .filter(|block| block.prefix <= prefix)
+            .collect();
+        match strategy {
+            FitStrategy::FirstFit => blocks.sort_by_key(|block| block.start),
+            FitStrategy::BestFit => blocks.sort_by_key(|block| (block.gap, block.start)),
+            FitStrategy::KeepLarge => {
+                blocks.sort_by_key(|block| (Reverse(block.prefix), block.start))
+            }
+        }
+
+        // Subnets are taken from the start of each block, so what is left of it stays aligned
+        let subnet_size_minus_one = block_size_minus_one(bits - prefix);
+        let subnets: Vec<Cidr> = blocks
+            .iter()
+            .flat_map(|block| {
+                (0..=block_size_minus_one(prefix - block.prefix)).map(move |index| {
+                    let start = block.start + index * subnet_size_minus_one + index;
+                    Cidr::from_block(start, prefix, bits)
+                })
+            })
+            .take(count)
+            .collect();
+        match subnets.len() < count {
+            true => Err(AllocateError::InsufficientSpace {
+                count,
+                found: subnets.len(),
+                prefix,
+                pool: *self,
+            }),
+            false => Ok(subnets),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllocateError, FitStrategy};
+    use crate::cidr::{Cidr, cidrs, ip_set};
+
+    #[test]
+    fn test_allocate_strategies() {
+        // Arrange
+        // Free gaps: 10.0.1.0 - 10.0.3.255 (a /24 and a /23) and 10.0.6.0/23
+        let expected_pool: Cidr = "10.0.0.0/21".parse().unwrap();
+        let expected_used = ip_set(&["10.0.0.0/24", "10.0.4.0/23"]);
+        let expected_subnets = [
+            (
+                FitStrategy::FirstFit,
+                cidrs(&["10.0.1.0/24", "10.0.2.0/24"]),
+            ),
+            (FitStrategy::BestFit, cidrs(&["10.0.6.0/24", "10.0.7.0/24"])),
+            (
+                FitStrategy::KeepLarge,
+                cidrs(&["10.0.1.0/24", "10.0.2.0/24"]),
+            ),
+        ];
+
+        for (strategy, expected_subnets) in expected_subnets {
+            // Act
+            let actual_subnets = expected_pool
+                .allocate(&expected_used, 24, 2, strategy)
+                .unwrap();
+
+            // Assert
+            assert_eq!(actual_subnets, expected_subnets, "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn test_allocate_keep_large_leaves_aligned_blocks_whole() {
+        // Arrange
+        // One free gap: 10.0.0.0 - 10.0.3.127 (a /23, a /24 and a /25)
+        let expected_pool: Cidr = "10.0.0.0/22".parse().unwrap();
+        let expected_used = ip_set(&["10.0.3.128/25"]);
+
+        // Act
+        let actual_first_fit = expected_pool
+            .allocate(&expected_used, 25, 2, FitStrategy::FirstFit)
+            .unwrap();
+        let actual_keep_large = expected_pool
+            .allocate(&expected_used, 25, 2, FitStrategy::KeepLarge)
+            .unwrap();
+
+        // Assert
+        assert_eq!(actual_first_fit, cidrs(&["10.0.0.0/25", "10.0.0.128/25"]));
+        assert_eq!(actual_keep_large, cidrs(&["10.0.3.0/25", "10.0.2.0/25"]));
+    }
+
+    #[test]
+    fn test_allocate_ipv6_ignores_other_family() {
+        // Arrange
+        let expected_pool: Cidr = "2001:db8::/32".parse().unwrap();
+        let expected_used = ip_set(&["2001:db8::/48", "10.0.0.0/8"]);
+
+        // Act
+        let actual_subnets = expected_pool
+            .allocate(&expected_used, 48, 1, FitStrategy::FirstFit)
+            .unwrap();
+
+        // Assert
+        assert_eq!(actual_subnets, cidrs(&["2001:db8:1::/48"]));
+    }
+
+    #[test]
+    fn test_allocate_errors() {
+        // Arrange
+        let expected_pool: Cidr = "10.0.0.0/23".parse().unwrap();
+        let expected_used = ip_set(&["10.0.0.0/24"]);
+
+        // Act
+        let actual_invalid_prefix =
+            expected_pool.allocate(&expected_used, 22, 1, FitStrategy::FirstFit);
+        let actual_insufficient_space =
+            expected_pool.allocate(&expected_used, 24, 2, FitStrategy::FirstFit);
+
+        // Assert
+        assert_eq!(
+            actual_invalid_prefix,
+            Err(AllocateError::InvalidPrefix {
+                new: 22,
+                current: 23,
+                max: 32
+            })
+        );
+        assert_eq!(
+            actual_insufficient_space,
+            Err(AllocateError::InsufficientSpace {
+                count: 2,
+                found: 1,
+                prefix: 24,
+                pool: expected_pool
+            })
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Binary, hex and integer renderings of addresses and masks
+
+use serde::Serialize;
+
+/// Address or mask in binary, hex and integer form
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct AddressBits {
+    /// Octets (IPv4) or groups (IPv6) in binary, with a `|` between the network and the host bits
+    pub binary: String,
+    pub hex: String,
+    pub integer: u128,
+}
+
+impl AddressBits {
+    fn new(value: u128, bits: u8, prefix: u8) -> Self {
+        let (group_len, separator) = match bits {
+            32 => (8, '.'),
+            _ => (16, ':'),
+        };
+        let mut binary = String::new();
+        for bit in 0..bits {
+            if bit == prefix {
+                binary.push('|');
+            } else if bit > 0 && bit % group_len == 0 {
+                binary.push(separator);
+            }
+            binary.push(match value >> (bits - 1 - bit) & 1 {
+                1 => '1',
+                _ => '0',
+            });
+        }
+        if prefix == bits {
+            binary.push('|');
+        }
+        AddressBits {
+            binary,
+            hex: format!("0x{:0width$x}", value, width = usize::from(bits / 4)),
+            integer: value,
+        }
+    }
+}
+
+/// Address, masks and bounds of a network rendered bit by bit
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Bits {
+    pub address: AddressBits,
+    pub netmask: AddressBits,
+    pub hostmask: AddressBits,
+    pub network: AddressBits,
+    /// Last address of the network, the broadcast for IPv4 networks that have one
+    pub last_address: AddressBits,
+}
+
+impl Bits {
+    fn new(address: u128, prefix: u8, bits: u8) -> Self {
+        let all_ones = u128::MAX >> (128 - bits);
+        let hostmask = all_ones.checked_shr(prefix.into()).unwrap_or(0);
+        let netmask = all_ones ^ hostmask;
+        let network = address & netmask;
+        Bits {
+            address: AddressBits::new(address, bits, prefix),
+            netmask: AddressBits::new(netmask, bits, prefix),
+            hostmask: AddressBits::new(hostmask, bits, prefix),
+            network: AddressBits::new(network, bits, prefix),
+            last_address: AddressBits::new(network | hostmask, bits, prefix),
+        }
+    }
+
+    pub(crate) fn v4(address: u32, prefix: u8) -> Self {
+        Bits::new(address.into(), prefix, 32)
+    }
+
+    pub(crate) fn v6(address: u128, prefix: u8) -> Self {
+        Bits::new(address, prefix, 128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AddressBits, Bits};
+
+    #[test]
+    fn test_v4_bits() {
+        // Arrange
+        let expected_address = AddressBits {
+            binary: "00001010.00010110.1000|0111.10010000".to_string(),
+            hex: "0x0a168790".to_string(),
+            integer: 169248656,
+        };
+        let expected_netmask_binary = "11111111.11111111.1111|0000.00000000";
+        let expected_last_address_hex = "0x0a168fff";
+
+        // Act
+        let actual_bits = Bits::v4(0x0a168790, 20);
+
+        // Assert
+        assert_eq!(actual_bits.address, expected_address);
+        assert_eq!(actual_bits.netmask.binary, expected_netmask_binary);
+        assert_eq!(actual_bits.last_address.hex, expected_last_address_hex);
+    }
+
+    #[test]
+    fn test_boundary_on_a_separator() {
+        // Arrange
+        let expected_binaries = [
+            (24, "00001010.00010110.10000111|10010000"),
+            (0, "|00001010.00010110.10000111.10010000"),
+            (32, "00001010.00010110.10000111.10010000|"),
+        ];
+
+        for (prefix, expected_binary) in expected_binaries {
+            // Act
+            let actual_bits = Bits::v4(0x0a168790, prefix);
+
+            // Assert
+            assert_eq!(actual_bits.address.binary, expected_binary);
+        }
+    }
+
+    #[test]
+    fn test_v6_bits() {
+        // Arrange
+        let expected_hex = "0x20010db8000000000000000000000001";
+        let expected_binary_start = "0010000000000001:0000110110111000|0000000000000000:";
+
+        // Act
+        let actual_bits = Bits::v6(0x20010db8000000000000000000000001, 32);
+
+        // Assert
+        assert_eq!(actual_bits.address.hex, expected_hex);
+        assert!(
+            actual_bits
+                .address
+                .binary
+                .starts_with(expected_binary_start)
+        );
+        assert_eq!(
+            actual_bits.hostmask.integer,
+            u128::from(u32::MAX) << 64 | u128::from(u64::MAX)
+        );
+    }
+}
@@ -1,51 +1,174 @@
-use ipnet::{AddrParseError, Ipv6Net};
+use crate::range::{IPV4_BITS, IPV6_BITS, block_last};
+use ipnet::Ipv6Net;
 use ipv4::Ipv4Cidr;
 use ipv4::Ipv4CidrParseError;
+use ipv4::Network;
+use ipv6::{Ipv6CidrParseError, parse_ipv6_cidr};
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv6Addr};
+use std::ops::Range;
 use std::str::FromStr;
 use thiserror::Error;
 
 pub mod ipv4;
 pub mod ipv6;
 
-#[derive(Debug, Error)]
+/// Why a string is not a CIDR. Strings containing ':' are diagnosed as IPv6, the rest as IPv4
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
 pub enum CidrParseError {
-    #[error("Not a valid CIDR (v4 or v6)")]
-    Neither {
-        v4: Ipv4CidrParseError,
-        v6: AddrParseError,
-    },
+    #[error(transparent)]
+    V4(#[from] Ipv4CidrParseError),
+    #[error(transparent)]
+    V6(#[from] Ipv6CidrParseError),
+}
+
+impl CidrParseError {
+    /// Bytes of the input the error points at. Empty when something is missing at its start
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            CidrParseError::V4(v4) => v4.span(),
+            CidrParseError::V6(v6) => v6.span(),
+        }
+    }
+
+    /// What was expected at the span
+    pub fn expected(&self) -> String {
+        match self {
+            CidrParseError::V4(v4) => v4.expected(),
+            CidrParseError::V6(v6) => v6.expected(),
+        }
+    }
 }
 
 /// Enum containing IPv4 and IPv6 varaiants
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Cidr {
     V4(Ipv4Cidr),
     V6(Ipv6Net),
 }
 
+impl Cidr {
+    pub fn prefix_len(&self) -> u8 {
+        match self {
+            Cidr::V4(v4) => v4.prefix_len(),
+            Cidr::V6(v6) => v6.prefix_len(),
+        }
+    }
+
+    /// Whether the address belongs to this network. Always `false` across families
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self, addr) {
+            (Cidr::V4(v4), IpAddr::V4(addr)) => v4.contains(addr),
+            (Cidr::V6(v6), IpAddr::V6(addr)) => v6.contains(&addr),
+            _ => false,
+        }
+    }
+
+    /// Whether this network lies inside `other`. A network is a subnet of itself
+    pub fn is_subnet_of(&self, other: &Cidr) -> bool {
+        match (self, other) {
+            (Cidr::V4(v4), Cidr::V4(other)) => v4.is_subnet_of(other),
+            (Cidr::V6(v6), Cidr::V6(other)) => other.contains(v6),
+            _ => false,
+        }
+    }
+
+    /// Whether `other` lies inside this network. A network is a supernet of itself
+    pub fn is_supernet_of(&self, other: &Cidr) -> bool {
+        other.is_subnet_of(self)
+    }
+
+    /// Whether the networks share at least one address
+    ///
+    /// Two CIDR blocks either are disjoint or one contains the other
+    pub fn overlaps(&self, other: &Cidr) -> bool {
+        self.is_subnet_of(other) || self.is_supernet_of(other)
+    }
+
+    /// Address width of the family in bits
+    pub(crate) fn bits(&self) -> u8 {
+        match self {
+            Cidr::V4(_) => IPV4_BITS,
+            Cidr::V6(_) => IPV6_BITS,
+        }
+    }
+
+    /// First and last address of the network, widened to `u128`
+    pub(crate) fn range(&self) -> (u128, u128) {
+        let first = match self {
+            Cidr::V4(v4) => u128::from(v4.get_network_address()),
+            Cidr::V6(v6) => u128::from(v6.network()),
+        };
+        (first, block_last(first, self.prefix_len(), self.bits()))
+    }
+
+    /// Builds a network of the family with `bits` address width from an aligned block
+    pub(crate) fn from_block(start: u128, prefix: u8, bits: u8) -> Self {
+        match bits {
+            IPV4_BITS => Cidr::V4(Ipv4Cidr::from_valid_parts(start as u32, prefix)),
+            _ => Cidr::V6(Ipv6Net::new_assert(Ipv6Addr::from(start), prefix)),
+        }
+    }
+}
+
+/// Single host network: /32 for IPv4 and /128 for IPv6
+impl From<IpAddr> for Cidr {
+    fn from(value: IpAddr) -> Self {
+        match value {
+            IpAddr::V4(v4) => Cidr::V4(Ipv4Cidr::from_valid_parts(v4.into(), IPV4_BITS)),
+            IpAddr::V6(v6) => Cidr::V6(Ipv6Net::from(v6)),
+        }
+    }
+}
+
+impl Display for Cidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cidr::V4(v4) => v4.fmt(f),
+            Cidr::V6(v6) => v6.fmt(f),
+        }
+    }
+}
+
 impl FromStr for Cidr {
     type Err = CidrParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let v4_err = match s.parse::<Ipv4Cidr>() {
-            Ok(v4) => return Ok(Cidr::V4(v4)),
-            Err(e) => e,
-        };
+        match s.contains(':') {
+            true => Ok(Cidr::V6(parse_ipv6_cidr(s)?)),
+            false => Ok(Cidr::V4(s.parse()?)),
+        }
+    }
+}
 
-        let v6_err = match s.parse::<Ipv6Net>() {
-            Ok(v6) => return Ok(Cidr::V6(v6)),
-            Err(e) => e,
-        };
+/// Parses a CIDR for tests, panicking on an invalid one
+#[cfg(test)]
+pub(crate) fn cidr(cidr: &str) -> Cidr {
+    cidr.parse().unwrap()
+}
 
-        Err(CidrParseError::Neither {
-            v4: v4_err,
-            v6: v6_err,
-        })
-    }
+/// Parses an IP for tests, panicking on an invalid one
+#[cfg(test)]
+pub(crate) fn ip(ip: &str) -> IpAddr {
+    ip.parse().unwrap()
+}
+
+/// Parses CIDRs for tests, panicking on invalid ones
+#[cfg(test)]
+pub(crate) fn cidrs(cidrs: &[&str]) -> Vec<Cidr> {
+    cidrs.iter().map(|cidr| cidr.parse().unwrap()).collect()
+}
+
+/// Parses CIDRs for tests into the set of their addresses
+#[cfg(test)]
+pub(crate) fn ip_set(cidrs: &[&str]) -> crate::ipset::IpSet {
+    self::cidrs(cidrs).into_iter().collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Cidr;
+    use super::{Cidr, CidrParseError};
+    use std::net::IpAddr;
 
     const EXPECTED_IPV4_PREFIX: u8 = 24;
     const EXPECTED_IPV6_PREFIX: u8 = 24;
@@ -100,4 +223,62 @@ mod tests {
         // Assert
         assert!(actual_err.is_err());
     }
+
+    #[test]
+    fn test_parse_error_follows_the_family() {
+        // Arrange
+        let expected_v4_message = "prefix 33 exceeds 32 for IPv4";
+        let expected_v6_message = "prefix 129 exceeds 128 for IPv6";
+
+        // Act
+        let actual_v4_err = "10.0.0.0/33".parse::<Cidr>().unwrap_err();
+        let actual_v6_err = "2001:db8::/129".parse::<Cidr>().unwrap_err();
+
+        // Assert
+        assert!(matches!(actual_v4_err, CidrParseError::V4(_)));
+        assert_eq!(actual_v4_err.to_string(), expected_v4_message);
+        assert_eq!(actual_v4_err.span(), 9..11);
+        assert!(matches!(actual_v6_err, CidrParseError::V6(_)));
+        assert_eq!(actual_v6_err.to_string(), expected_v6_message);
+    }
+
+    #[test]
+    fn test_from_ip_addr() {
+        // Arrange
+        let expected_ipv4: IpAddr = EXPECTED_IPV4_STR.parse().unwrap();
+        let expected_ipv6: IpAddr = EXPECTED_IPV6_STR.parse().unwrap();
+
+        // Act
+        let actual_ipv4_cidr = Cidr::from(expected_ipv4);
+        let actual_ipv6_cidr = Cidr::from(expected_ipv6);
+
+        // Assert
+        assert_eq!(
+            actual_ipv4_cidr.to_string(),
+            format!("{EXPECTED_IPV4_STR}/32")
+        );
+        assert_eq!(
+            actual_ipv6_cidr.to_string(),
+            format!("{EXPECTED_IPV6_STR}/128")
+        );
+    }
+
+    #[test]
+    fn test_contains_and_overlaps() {
+        // Arrange
+        let expected_ipv4_net: Cidr = "10.0.0.0/8".parse().unwrap();
+        let expected_ipv6_net: Cidr = "2001:db8::/32".parse().unwrap();
+        let expected_ipv6_subnet: Cidr = format!("{EXPECTED_IPV6_STR}/64").parse().unwrap();
+
+        // Act
+
+        // Assert
+        assert!(expected_ipv4_net.contains(EXPECTED_IPV4_STR.parse().unwrap()));
+        assert!(!expected_ipv4_net.contains(EXPECTED_IPV6_STR.parse().unwrap()));
+        assert!(expected_ipv6_net.contains(EXPECTED_IPV6_STR.parse().unwrap()));
+        assert!(expected_ipv6_subnet.is_subnet_of(&expected_ipv6_net));
+        assert!(expected_ipv6_net.is_supernet_of(&expected_ipv6_subnet));
+        assert!(expected_ipv6_net.overlaps(&expected_ipv6_subnet));
+        assert!(!expected_ipv6_net.overlaps(&expected_ipv4_net));
+    }
 }
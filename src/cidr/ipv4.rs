@@ -1,19 +1,76 @@
+use crate::bits::Bits;
+use crate::cidr::Cidr;
+use crate::inspector::InspectOptions;
 use crate::inspector::Inspectable;
 use crate::inspector::InspectionResult;
 use crate::inspector::ipv4::Ipv4InspectionResult;
 use crate::ip::ipv4::Address;
 use crate::ip::ipv4::IPv4;
+use crate::special::{globally_reachable, special_purpose};
+use crate::split::{SplitBy, SplitError, Splittable};
+use std::fmt::Display;
+use std::net::Ipv4Addr;
+use std::ops::Range;
 use std::str::FromStr;
 use thiserror::Error;
 
 const MAX_IPV4_CIDR_PREFIX_LEN: u8 = 32;
+const POINT_TO_POINT_PREFIX_LEN: u8 = 31;
 
-#[derive(Debug, Error, PartialEq)]
+/// Why a string is not an IPv4 CIDR. Offsets are byte offsets into the parsed string
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
 pub enum Ipv4CidrParseError {
-    #[error("Invalid CIDR format (expected x.x.x.x/x)")]
-    InvalidFormat,
-    #[error("Invalid CIDR")]
-    InvalidCidr,
+    #[error("missing prefix length after the address")]
+    MissingPrefix { offset: usize },
+    #[error("expected 4 octets, found {count}")]
+    OctetCount { count: usize, length: usize },
+    #[error("octet {index} '{value}' is not a number")]
+    InvalidOctet {
+        index: usize,
+        value: String,
+        offset: usize,
+    },
+    #[error("octet {index} '{value}' exceeds 255")]
+    OctetTooLarge {
+        index: usize,
+        value: String,
+        offset: usize,
+    },
+    #[error("prefix '{value}' is not a number")]
+    InvalidPrefix { value: String, offset: usize },
+    #[error("prefix {value} exceeds {max} for IPv4", max = MAX_IPV4_CIDR_PREFIX_LEN)]
+    PrefixTooLarge { value: String, offset: usize },
+}
+
+impl Ipv4CidrParseError {
+    /// Bytes of the input the error points at. Empty when something is missing at its start
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Ipv4CidrParseError::MissingPrefix { offset } => *offset..*offset,
+            Ipv4CidrParseError::OctetCount { length, .. } => 0..*length,
+            Ipv4CidrParseError::InvalidOctet { value, offset, .. }
+            | Ipv4CidrParseError::OctetTooLarge { value, offset, .. }
+            | Ipv4CidrParseError::InvalidPrefix { value, offset }
+            | Ipv4CidrParseError::PrefixTooLarge { value, offset } => {
+                *offset..*offset + value.len()
+            }
+        }
+    }
+
+    /// What was expected at the span
+    pub fn expected(&self) -> String {
+        match self {
+            Ipv4CidrParseError::MissingPrefix { .. } => "a prefix length e.g. /24".to_string(),
+            Ipv4CidrParseError::OctetCount { .. } => "4 octets e.g. 10.0.0.0".to_string(),
+            Ipv4CidrParseError::InvalidOctet { .. } | Ipv4CidrParseError::OctetTooLarge { .. } => {
+                "a number from 0 to 255".to_string()
+            }
+            Ipv4CidrParseError::InvalidPrefix { .. }
+            | Ipv4CidrParseError::PrefixTooLarge { .. } => {
+                format!("a number from 0 to {MAX_IPV4_CIDR_PREFIX_LEN}")
+            }
+        }
+    }
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -23,7 +80,7 @@ pub enum Ipv4CidrPartsError {
 }
 
 /// An internal representation of IPv4 CIDR
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Ipv4Cidr {
     ip: IPv4,
     mask: IPv4,
@@ -35,8 +92,10 @@ pub(crate) struct Ipv4CidrParts {
     prefix: u8,
 }
 
-trait Network {
+pub(crate) trait Network {
     fn get_network_address(&self) -> u32;
+    fn get_broadcast_address(&self) -> Option<u32>;
+    fn get_usable_range(&self) -> (u32, u32);
 }
 
 impl TryFrom<Ipv4CidrParts> for Ipv4Cidr {
@@ -46,30 +105,72 @@ impl TryFrom<Ipv4CidrParts> for Ipv4Cidr {
         if value.prefix > MAX_IPV4_CIDR_PREFIX_LEN {
             return Err(Ipv4CidrPartsError::InvalidPrefix(value.prefix));
         }
-        Ok(Self {
-            ip: IPv4::from(value.address),
-            mask: IPv4::from(!0u32 << (MAX_IPV4_CIDR_PREFIX_LEN - value.prefix)),
-            prefix: value.prefix,
-        })
+        Ok(Self::from_valid_parts(value.address, value.prefix))
     }
 }
 
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|byte| byte.is_ascii_digit())
+}
+
 impl FromStr for Ipv4Cidr {
     type Err = Ipv4CidrParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (ip_str, prefix) = s.split_once('/').ok_or(Ipv4CidrParseError::InvalidFormat)?;
-        let ip: IPv4 = ip_str
-            .parse::<IPv4>()
-            .map_err(|_| Ipv4CidrParseError::InvalidCidr)?;
-        let prefix: u8 = prefix
-            .parse::<u8>()
-            .map_err(|_| Ipv4CidrParseError::InvalidCidr)?;
-        let cidr_parts: Ipv4CidrParts = Ipv4CidrParts {
-            address: ip.addr(),
-            prefix,
+        let (ip_str, prefix_str) = s
+            .split_once('/')
+            .ok_or(Ipv4CidrParseError::MissingPrefix { offset: s.len() })?;
+
+        let octets: Vec<&str> = ip_str.split('.').collect();
+        if octets.len() != 4 {
+            return Err(Ipv4CidrParseError::OctetCount {
+                count: octets.len(),
+                length: ip_str.len(),
+            });
+        }
+        let mut address: u32 = 0;
+        let mut offset: usize = 0;
+        for (index, octet) in octets.iter().enumerate() {
+            let value: u8 = match (is_number(octet), octet.parse::<u8>()) {
+                (true, Ok(value)) => value,
+                (true, Err(_)) => {
+                    return Err(Ipv4CidrParseError::OctetTooLarge {
+                        index: index + 1,
+                        value: octet.to_string(),
+                        offset,
+                    });
+                }
+                (false, _) => {
+                    return Err(Ipv4CidrParseError::InvalidOctet {
+                        index: index + 1,
+                        value: octet.to_string(),
+                        offset,
+                    });
+                }
+            };
+            address = address << 8 | u32::from(value);
+            offset += octet.len() + 1;
+        }
+
+        let prefix_offset = ip_str.len() + 1;
+        if !is_number(prefix_str) {
+            return Err(Ipv4CidrParseError::InvalidPrefix {
+                value: prefix_str.to_string(),
+                offset: prefix_offset,
+            });
+        }
+        let too_large = || Ipv4CidrParseError::PrefixTooLarge {
+            value: prefix_str.to_string(),
+            offset: prefix_offset,
         };
-        Self::try_from(cidr_parts).map_err(|_| Ipv4CidrParseError::InvalidCidr)
+        let prefix: u8 = prefix_str.parse().map_err(|_| too_large())?;
+        Self::try_from(Ipv4CidrParts { address, prefix }).map_err(|_| too_large())
+    }
+}
+
+impl Display for Ipv4Cidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.ip, self.prefix)
     }
 }
 
@@ -77,47 +178,179 @@ impl Network for Ipv4Cidr {
     fn get_network_address(&self) -> u32 {
         self.ip.addr() & self.mask.addr()
     }
+
+    /// /31 (RFC 3021 point-to-point) and /32 (single host) networks have no broadcast address
+    fn get_broadcast_address(&self) -> Option<u32> {
+        match self.prefix {
+            POINT_TO_POINT_PREFIX_LEN..=MAX_IPV4_CIDR_PREFIX_LEN => None,
+            _ => Some(self.get_network_address() | !self.mask.addr()),
+        }
+    }
+
+    /// First and last usable host addresses, i.e. hosts 1 and -1
+    ///
+    /// Both addresses of a /31 are usable hosts (RFC 3021) and a /32 is the only host itself
+    fn get_usable_range(&self) -> (u32, u32) {
+        let host = |host| {
+            self.host(host)
+                .map(u32::from)
+                .expect("every network has a first and a last usable host")
+        };
+        (host(1), host(-1))
+    }
 }
 
 impl Ipv4Cidr {
-    pub(crate) fn prefix_len(&self) -> u8 {
+    /// Builds a CIDR from a prefix length that is known to be <= 32
+    pub(crate) fn from_valid_parts(address: u32, prefix: u8) -> Self {
+        Self {
+            ip: IPv4::from(address),
+            mask: IPv4::from(
+                (!0u32)
+                    .checked_shl((MAX_IPV4_CIDR_PREFIX_LEN - prefix).into())
+                    .unwrap_or(0),
+            ),
+            prefix,
+        }
+    }
+
+    pub fn prefix_len(&self) -> u8 {
         self.prefix
     }
 
-    pub(crate) fn addr(&self) -> IPv4 {
-        self.ip
+    /// Address as it was given, host bits included
+    pub fn addr(&self) -> Ipv4Addr {
+        self.ip.into()
+    }
+
+    pub fn network(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.get_network_address())
+    }
+
+    pub fn netmask(&self) -> Ipv4Addr {
+        self.mask.into()
+    }
+
+    /// Inverse of the netmask, also known as the wildcard mask
+    pub fn hostmask(&self) -> Ipv4Addr {
+        Ipv4Addr::from(!self.mask.addr())
+    }
+
+    /// Whether the address belongs to this network
+    pub fn contains(&self, addr: Ipv4Addr) -> bool {
+        u32::from(addr) & self.mask.addr() == self.get_network_address()
+    }
+
+    /// Whether this network lies inside `other`. A network is a subnet of itself
+    pub fn is_subnet_of(&self, other: &Ipv4Cidr) -> bool {
+        self.prefix >= other.prefix && other.contains(self.network())
+    }
+
+    /// Whether `other` lies inside this network. A network is a supernet of itself
+    pub fn is_supernet_of(&self, other: &Ipv4Cidr) -> bool {
+        other.is_subnet_of(self)
+    }
+
+    /// Whether the networks share at least one address
+    ///
+    /// Two CIDR blocks either are disjoint or one contains the other
+    pub fn overlaps(&self, other: &Ipv4Cidr) -> bool {
+        self.is_subnet_of(other) || self.is_supernet_of(other)
     }
 }
 
 impl Inspectable for Ipv4Cidr {
-    fn inspect(&self) -> InspectionResult {
+    fn inspect_with(&self, options: &InspectOptions) -> InspectionResult {
         let prefix_len = self.prefix_len();
         let human_readable_ip_part = self.addr().to_string();
         let network_address = self.get_network_address();
-        let first_usable_ip = network_address + 1;
-        let broadcast_address = network_address + (!0u32 >> prefix_len);
-        let last_usable_ip = broadcast_address - 1;
+        let (first_usable_ip, last_usable_ip) = self.get_usable_range();
+        let hostmask = !self.mask.addr();
+        let special_purpose = special_purpose(&Cidr::V4(*self));
         InspectionResult::V4(Ipv4InspectionResult {
             cidr: format!("{human_readable_ip_part}/{prefix_len}"),
             first_usable: IPv4::from(first_usable_ip).to_string(),
             last_usable: IPv4::from(last_usable_ip).to_string(),
             network: IPv4::from(network_address).to_string(),
-            broadcast: IPv4::from(broadcast_address).to_string(),
+            broadcast: self
+                .get_broadcast_address()
+                .map(|broadcast_address| IPv4::from(broadcast_address).to_string()),
             address: human_readable_ip_part,
             prefix_length: prefix_len,
+            netmask: self.mask.to_string(),
+            hostmask: IPv4::from(hostmask).to_string(),
+            total_addresses: 1 << (MAX_IPV4_CIDR_PREFIX_LEN - prefix_len),
+            usable_addresses: u64::from(last_usable_ip.abs_diff(first_usable_ip)) + 1,
+            globally_reachable: globally_reachable(&special_purpose),
+            special_purpose,
+            classful: options.classful.then(|| self.classful()),
+            bits: options.bits.then(|| Bits::v4(self.ip.addr(), prefix_len)),
+        })
+    }
+}
+
+/// Lazy iterator over equal subnets of an [`Ipv4Cidr`]
+#[derive(Debug)]
+pub struct Ipv4Subnets {
+    next: u64,
+    end: u64,
+    prefix: u8,
+}
+
+impl Iterator for Ipv4Subnets {
+    type Item = Ipv4Cidr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let subnet = Ipv4Cidr::from_valid_parts(self.next as u32, self.prefix);
+        self.next += 1 << (MAX_IPV4_CIDR_PREFIX_LEN - self.prefix);
+        Some(subnet)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let step: u64 = 1 << (MAX_IPV4_CIDR_PREFIX_LEN - self.prefix);
+        self.next = (n as u64)
+            .checked_mul(step)
+            .and_then(|skipped| self.next.checked_add(skipped))
+            .unwrap_or(self.end);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.next) >> (MAX_IPV4_CIDR_PREFIX_LEN - self.prefix);
+        (remaining as usize, Some(remaining as usize))
+    }
+}
+
+impl Splittable for Ipv4Cidr {
+    type Subnets = Ipv4Subnets;
+
+    fn split(&self, by: SplitBy) -> Result<Self::Subnets, SplitError> {
+        let prefix = by.prefix_len(self.prefix, MAX_IPV4_CIDR_PREFIX_LEN)?;
+        let network_address = u64::from(self.get_network_address());
+        Ok(Ipv4Subnets {
+            next: network_address,
+            end: network_address + u64::from(!self.mask.addr()) + 1,
+            prefix,
         })
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Ipv4CidrParseError::InvalidCidr;
     use super::Network;
     use super::{Ipv4Cidr, Ipv4CidrParseError, Ipv4CidrParts, Ipv4CidrPartsError};
+    use crate::classful::{AddressClass, ClassfulBoundary};
+    use crate::inspector::InspectOptions;
     use crate::inspector::Inspectable;
     use crate::inspector::InspectionResult;
     use crate::inspector::ipv4::Ipv4InspectionResult;
     use crate::ip::ipv4::{Address, IPv4};
+    use crate::special::SpecialPurpose;
+    use crate::split::{SplitBy, Splittable};
+    use std::net::Ipv4Addr;
 
     const EXPECTED_BINARY_ADDRESS: u32 = 0b00001010_00010110_10000111_10010000;
     const EXPECTED_IPV4_STR: &str = "10.22.135.144";
@@ -188,7 +421,12 @@ mod test {
         let actual_result: Result<Ipv4Cidr, Ipv4CidrParseError> = EXPECTED_IPV4_STR.parse();
 
         // Assert
-        assert_eq!(actual_result, Err(Ipv4CidrParseError::InvalidFormat));
+        assert_eq!(
+            actual_result,
+            Err(Ipv4CidrParseError::MissingPrefix {
+                offset: EXPECTED_IPV4_STR.len()
+            })
+        );
     }
 
     #[test]
@@ -202,7 +440,85 @@ mod test {
         let actual_result: Result<Ipv4Cidr, Ipv4CidrParseError> = expected_cidr_string.parse();
 
         // Assert
-        assert_eq!(actual_result, Err(InvalidCidr));
+        assert_eq!(
+            actual_result,
+            Err(Ipv4CidrParseError::InvalidOctet {
+                index: 3,
+                value: "not_a_number".to_string(),
+                offset: 8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ipv4_cidr_errors_point_at_the_problem() {
+        // Arrange
+        let expected_errors = [
+            (
+                "10.0.256.0/24",
+                Ipv4CidrParseError::OctetTooLarge {
+                    index: 3,
+                    value: "256".to_string(),
+                    offset: 5,
+                },
+                5..8,
+            ),
+            (
+                "10.0.0.0/33",
+                Ipv4CidrParseError::PrefixTooLarge {
+                    value: "33".to_string(),
+                    offset: 9,
+                },
+                9..11,
+            ),
+            (
+                "10.0.0.0/",
+                Ipv4CidrParseError::InvalidPrefix {
+                    value: "".to_string(),
+                    offset: 9,
+                },
+                9..9,
+            ),
+            (
+                "10.0..0/8",
+                Ipv4CidrParseError::InvalidOctet {
+                    index: 3,
+                    value: "".to_string(),
+                    offset: 5,
+                },
+                5..5,
+            ),
+            (
+                "10.0.0/8",
+                Ipv4CidrParseError::OctetCount {
+                    count: 3,
+                    length: 6,
+                },
+                0..6,
+            ),
+        ];
+
+        for (input, expected_error, expected_span) in expected_errors {
+            // Act
+            let actual_error = input.parse::<Ipv4Cidr>().unwrap_err();
+
+            // Assert
+            assert_eq!(actual_error, expected_error);
+            assert_eq!(actual_error.span(), expected_span);
+        }
+    }
+
+    #[test]
+    fn test_parse_ipv4_cidr_error_message() {
+        // Arrange
+        let expected_message = "octet 3 '256' exceeds 255";
+
+        // Act
+        let actual_error = "10.0.256.0/24".parse::<Ipv4Cidr>().unwrap_err();
+
+        // Assert
+        assert_eq!(actual_error.to_string(), expected_message);
+        assert_eq!(actual_error.expected(), "a number from 0 to 255");
     }
 
     #[test]
@@ -240,10 +556,24 @@ mod test {
                 cidr: expected_cidr_string,
                 first_usable: expected_first_usable_ip,
                 last_usable: expected_last_usable_ip,
-                broadcast: expected_broadcast_ip,
+                broadcast: Some(expected_broadcast_ip),
                 network: expected_subnet_address,
                 address: EXPECTED_IPV4_STR.to_string(),
                 prefix_length: expected_prefix,
+                netmask: "255.255.255.0".to_string(),
+                hostmask: "0.0.0.255".to_string(),
+                total_addresses: 256,
+                usable_addresses: 254,
+                special_purpose: vec![SpecialPurpose {
+                    block: "10.0.0.0/8".to_string(),
+                    name: "Private-Use".to_string(),
+                    rfc: "RFC 1918".to_string(),
+                    globally_reachable: false,
+                    partial: false,
+                }],
+                globally_reachable: false,
+                classful: None,
+                bits: None,
             });
         let expected_cidr = Ipv4Cidr {
             ip: IPv4::from(EXPECTED_BINARY_ADDRESS),
@@ -257,4 +587,254 @@ mod test {
         // Assert
         assert_eq!(actual_inspection_result, expected_inspection_result);
     }
+
+    #[test]
+    fn test_inspect_point_to_point() {
+        // Arrange
+        let expected_cidr_string: &str = "10.22.135.144/31";
+        let expected_inspection_result: InspectionResult =
+            InspectionResult::V4(Ipv4InspectionResult {
+                cidr: expected_cidr_string.to_string(),
+                first_usable: "10.22.135.144".to_string(),
+                last_usable: "10.22.135.145".to_string(),
+                broadcast: None,
+                network: "10.22.135.144".to_string(),
+                address: EXPECTED_IPV4_STR.to_string(),
+                prefix_length: 31,
+                netmask: "255.255.255.254".to_string(),
+                hostmask: "0.0.0.1".to_string(),
+                total_addresses: 2,
+                usable_addresses: 2,
+                special_purpose: vec![SpecialPurpose {
+                    block: "10.0.0.0/8".to_string(),
+                    name: "Private-Use".to_string(),
+                    rfc: "RFC 1918".to_string(),
+                    globally_reachable: false,
+                    partial: false,
+                }],
+                globally_reachable: false,
+                classful: None,
+                bits: None,
+            });
+        let expected_cidr: Ipv4Cidr = expected_cidr_string.parse().unwrap();
+
+        // Act
+        let actual_inspection_result: InspectionResult = expected_cidr.inspect();
+
+        // Assert
+        assert_eq!(actual_inspection_result, expected_inspection_result);
+    }
+
+    #[test]
+    fn test_inspect_single_host() {
+        // Arrange
+        let expected_cidr_string: &str = "255.255.255.255/32";
+        let expected_inspection_result: InspectionResult =
+            InspectionResult::V4(Ipv4InspectionResult {
+                cidr: expected_cidr_string.to_string(),
+                first_usable: "255.255.255.255".to_string(),
+                last_usable: "255.255.255.255".to_string(),
+                broadcast: None,
+                network: "255.255.255.255".to_string(),
+                address: "255.255.255.255".to_string(),
+                prefix_length: 32,
+                netmask: "255.255.255.255".to_string(),
+                hostmask: "0.0.0.0".to_string(),
+                total_addresses: 1,
+                usable_addresses: 1,
+                special_purpose: vec![
+                    SpecialPurpose {
+                        block: "240.0.0.0/4".to_string(),
+                        name: "Reserved".to_string(),
+                        rfc: "RFC 1112".to_string(),
+                        globally_reachable: false,
+                        partial: false,
+                    },
+                    SpecialPurpose {
+                        block: "255.255.255.255/32".to_string(),
+                        name: "Limited Broadcast".to_string(),
+                        rfc: "RFC 919".to_string(),
+                        globally_reachable: false,
+                        partial: false,
+                    },
+                ],
+                globally_reachable: false,
+                classful: None,
+                bits: None,
+            });
+        let expected_cidr: Ipv4Cidr = expected_cidr_string.parse().unwrap();
+
+        // Act
+        let actual_inspection_result: InspectionResult = expected_cidr.inspect();
+
+        // Assert
+        assert_eq!(actual_inspection_result, expected_inspection_result);
+    }
+
+    #[test]
+    fn test_inspect_whole_space() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "0.0.0.0/0".parse().unwrap();
+
+        // Act
+        let actual_inspection_result: InspectionResult = expected_cidr.inspect();
+
+        // Assert
+        match actual_inspection_result {
+            InspectionResult::V4(v4) => {
+                assert_eq!(v4.netmask, "0.0.0.0");
+                assert_eq!(v4.hostmask, "255.255.255.255");
+                assert_eq!(v4.total_addresses, 1 << 32);
+                assert_eq!(v4.usable_addresses, (1 << 32) - 2);
+            }
+            _ => panic!("Expected InspectionResult::V4"),
+        }
+    }
+
+    #[test]
+    fn test_inspect_with_classful() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "192.168.0.0/22".parse().unwrap();
+        let expected_options = InspectOptions {
+            classful: true,
+            ..Default::default()
+        };
+
+        // Act
+        let actual_inspection_result: InspectionResult =
+            expected_cidr.inspect_with(&expected_options);
+
+        // Assert
+        match actual_inspection_result {
+            InspectionResult::V4(v4) => {
+                let classful = v4.classful.unwrap();
+                assert_eq!(classful.class, AddressClass::C);
+                assert_eq!(classful.network.as_deref(), Some("192.168.0.0/24"));
+                assert_eq!(classful.boundary, Some(ClassfulBoundary::Supernet));
+            }
+            _ => panic!("Expected InspectionResult::V4"),
+        }
+    }
+
+    #[test]
+    fn test_masks() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "10.22.135.144/20".parse().unwrap();
+        let expected_netmask: Ipv4Addr = "255.255.240.0".parse().unwrap();
+        let expected_hostmask: Ipv4Addr = "0.0.15.255".parse().unwrap();
+
+        // Act
+        let (actual_netmask, actual_hostmask) = (expected_cidr.netmask(), expected_cidr.hostmask());
+
+        // Assert
+        assert_eq!(actual_netmask, expected_netmask);
+        assert_eq!(actual_hostmask, expected_hostmask);
+    }
+
+    #[test]
+    fn test_get_broadcast_address_whole_space() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "10.22.135.144/0".parse().unwrap();
+
+        // Act
+        let actual_broadcast_address: Option<u32> = expected_cidr.get_broadcast_address();
+
+        // Assert
+        assert_eq!(actual_broadcast_address, Some(u32::MAX));
+    }
+
+    #[test]
+    fn test_split_by_prefix() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "10.22.135.144/22".parse().unwrap();
+        let expected_subnets: Vec<Ipv4Cidr> = vec![
+            "10.22.132.0/23".parse().unwrap(),
+            "10.22.134.0/23".parse().unwrap(),
+        ];
+
+        // Act
+        let actual_subnets: Vec<Ipv4Cidr> =
+            expected_cidr.split(SplitBy::Prefix(23)).unwrap().collect();
+
+        // Assert
+        assert_eq!(actual_subnets, expected_subnets);
+    }
+
+    #[test]
+    fn test_split_whole_space_by_count() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "0.0.0.0/0".parse().unwrap();
+
+        // Act
+        let mut actual_subnets = expected_cidr.split(SplitBy::Count(4)).unwrap();
+
+        // Assert
+        assert_eq!(actual_subnets.size_hint(), (4, Some(4)));
+        assert_eq!(actual_subnets.nth(3), Some("192.0.0.0/2".parse().unwrap()));
+        assert_eq!(actual_subnets.next(), None);
+    }
+
+    #[test]
+    fn test_split_is_lazy() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "0.0.0.0/0".parse().unwrap();
+
+        // Act
+        let actual_last_subnet: Option<Ipv4Cidr> = expected_cidr
+            .split(SplitBy::Prefix(32))
+            .unwrap()
+            .nth(u32::MAX as usize);
+
+        // Assert
+        assert_eq!(
+            actual_last_subnet,
+            Some("255.255.255.255/32".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_contains() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "10.22.135.144/24".parse().unwrap();
+
+        // Act
+        let actual_inside: bool = expected_cidr.contains("10.22.135.1".parse().unwrap());
+        let actual_outside: bool = expected_cidr.contains("10.22.136.1".parse().unwrap());
+
+        // Assert
+        assert!(actual_inside);
+        assert!(!actual_outside);
+    }
+
+    #[test]
+    fn test_subnet_and_supernet() {
+        // Arrange
+        let expected_supernet: Ipv4Cidr = "10.0.0.0/8".parse().unwrap();
+        let expected_subnet: Ipv4Cidr = "10.22.135.144/24".parse().unwrap();
+        let expected_other: Ipv4Cidr = "192.168.0.0/16".parse().unwrap();
+
+        // Act
+
+        // Assert
+        assert!(expected_subnet.is_subnet_of(&expected_supernet));
+        assert!(expected_subnet.is_subnet_of(&expected_subnet));
+        assert!(!expected_supernet.is_subnet_of(&expected_subnet));
+        assert!(expected_supernet.is_supernet_of(&expected_subnet));
+        assert!(expected_supernet.overlaps(&expected_subnet));
+        assert!(expected_subnet.overlaps(&expected_supernet));
+        assert!(!expected_other.overlaps(&expected_supernet));
+    }
+
+    #[test]
+    fn test_whole_space_contains_everything() {
+        // Arrange
+        let expected_cidr: Ipv4Cidr = "0.0.0.0/0".parse().unwrap();
+        let expected_subnet: Ipv4Cidr = "255.255.255.255/32".parse().unwrap();
+
+        // Act
+
+        // Assert
+        assert!(expected_cidr.contains("255.255.255.255".parse().unwrap()));
+        assert!(expected_cidr.is_supernet_of(&expected_subnet));
+    }
 }
@@ -1,9 +1,127 @@
-use ipnet::Ipv6Net;
+use ipnet::{Ipv6Net, Ipv6Subnets};
+use std::net::Ipv6Addr;
+use std::ops::Range;
+use thiserror::Error;
 
-use crate::inspector::{Inspectable, InspectionResult, ipv6::Ipv6InspectionResult};
+use crate::bits::Bits;
+use crate::cidr::Cidr;
+use crate::inspector::{InspectOptions, Inspectable, InspectionResult, ipv6::Ipv6InspectionResult};
+use crate::special::{globally_reachable, special_purpose};
+use crate::split::{SplitBy, SplitError, Splittable};
 
 const MAX_IPV6_CIDR_PREFIX_LEN: u8 = 128;
 const MIN_REASONABLE_PREFIX_LEN: u8 = 96;
+const POINT_TO_POINT_PREFIX_LEN: u8 = 127;
+
+/// Why a string is not an IPv6 CIDR. Offsets are byte offsets into the parsed string
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum Ipv6CidrParseError {
+    #[error("missing prefix length after the address")]
+    MissingPrefix { offset: usize },
+    #[error("'{character}' is not a hex digit")]
+    InvalidCharacter { character: char, offset: usize },
+    #[error("group '{value}' has more than 4 hex digits")]
+    GroupTooLong { value: String, offset: usize },
+    #[error("'::' can only appear once")]
+    RepeatedDoubleColon { offset: usize },
+    #[error("'{value}' is not a valid IPv6 address")]
+    InvalidAddress { value: String },
+    #[error("prefix '{value}' is not a number")]
+    InvalidPrefix { value: String, offset: usize },
+    #[error("prefix {value} exceeds {max} for IPv6", max = MAX_IPV6_CIDR_PREFIX_LEN)]
+    PrefixTooLarge { value: String, offset: usize },
+}
+
+impl Ipv6CidrParseError {
+    /// Bytes of the input the error points at. Empty when something is missing at its start
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Ipv6CidrParseError::MissingPrefix { offset } => *offset..*offset,
+            Ipv6CidrParseError::InvalidCharacter { character, offset } => {
+                *offset..*offset + character.len_utf8()
+            }
+            Ipv6CidrParseError::RepeatedDoubleColon { offset } => *offset..*offset + 2,
+            Ipv6CidrParseError::InvalidAddress { value } => 0..value.len(),
+            Ipv6CidrParseError::GroupTooLong { value, offset }
+            | Ipv6CidrParseError::InvalidPrefix { value, offset }
+            | Ipv6CidrParseError::PrefixTooLarge { value, offset } => {
+                *offset..*offset + value.len()
+            }
+        }
+    }
+
+    /// What was expected at the span
+    pub fn expected(&self) -> String {
+        match self {
+            Ipv6CidrParseError::MissingPrefix { .. } => "a prefix length e.g. /64".to_string(),
+            Ipv6CidrParseError::InvalidCharacter { .. } => "a hex digit or ':'".to_string(),
+            Ipv6CidrParseError::GroupTooLong { .. } => "up to 4 hex digits".to_string(),
+            Ipv6CidrParseError::RepeatedDoubleColon { .. } => "a single '::'".to_string(),
+            Ipv6CidrParseError::InvalidAddress { .. } => {
+                "8 groups of up to 4 hex digits e.g. 2001:db8::1".to_string()
+            }
+            Ipv6CidrParseError::InvalidPrefix { .. }
+            | Ipv6CidrParseError::PrefixTooLarge { .. } => {
+                format!("a number from 0 to {MAX_IPV6_CIDR_PREFIX_LEN}")
+            }
+        }
+    }
+}
+
+/// Finds the first problem of an address that failed to parse, scanning left to right
+fn diagnose_address(address: &str) -> Ipv6CidrParseError {
+    if let Some((offset, character)) = address
+        .char_indices()
+        .find(|(_, character)| !character.is_ascii_hexdigit() && !matches!(character, ':' | '.'))
+    {
+        return Ipv6CidrParseError::InvalidCharacter { character, offset };
+    }
+    if let Some(first) = address.find("::")
+        && let Some(second) = address[first + 2..].find("::")
+    {
+        return Ipv6CidrParseError::RepeatedDoubleColon {
+            offset: first + 2 + second,
+        };
+    }
+    let mut offset: usize = 0;
+    for group in address.split(':') {
+        // The last group may be an embedded IPv4 address e.g. ::ffff:10.0.0.1
+        if group.len() > 4 && !group.contains('.') {
+            return Ipv6CidrParseError::GroupTooLong {
+                value: group.to_string(),
+                offset,
+            };
+        }
+        offset += group.len() + 1;
+    }
+    Ipv6CidrParseError::InvalidAddress {
+        value: address.to_string(),
+    }
+}
+
+/// Parses an IPv6 CIDR, pointing at the first problem if it is not one
+pub(crate) fn parse_ipv6_cidr(s: &str) -> Result<Ipv6Net, Ipv6CidrParseError> {
+    let (address_str, prefix_str) = s
+        .split_once('/')
+        .ok_or(Ipv6CidrParseError::MissingPrefix { offset: s.len() })?;
+    let address: Ipv6Addr = address_str
+        .parse()
+        .map_err(|_| diagnose_address(address_str))?;
+
+    let prefix_offset = address_str.len() + 1;
+    if prefix_str.is_empty() || !prefix_str.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(Ipv6CidrParseError::InvalidPrefix {
+            value: prefix_str.to_string(),
+            offset: prefix_offset,
+        });
+    }
+    let too_large = || Ipv6CidrParseError::PrefixTooLarge {
+        value: prefix_str.to_string(),
+        offset: prefix_offset,
+    };
+    let prefix: u8 = prefix_str.parse().map_err(|_| too_large())?;
+    Ipv6Net::new(address, prefix).map_err(|_| too_large())
+}
 
 pub(crate) trait SubnetSize {
     fn subnet_size(&self) -> String;
@@ -21,9 +139,31 @@ impl SubnetSize for Ipv6Net {
     }
 }
 
+pub(crate) trait UsableRange {
+    fn usable_range(&self, reserve_subnet_router_anycast: bool) -> (Ipv6Addr, Ipv6Addr);
+}
+
+impl UsableRange for Ipv6Net {
+    /// First and last usable addresses
+    ///
+    /// The Subnet-Router anycast address is the network address itself (RFC 4291).
+    /// It is never reserved on /127 point-to-point links (RFC 6164) and /128 single hosts
+    fn usable_range(&self, reserve_subnet_router_anycast: bool) -> (Ipv6Addr, Ipv6Addr) {
+        let network_address = u128::from(self.network());
+        let first_usable =
+            match reserve_subnet_router_anycast && self.prefix_len() < POINT_TO_POINT_PREFIX_LEN {
+                true => network_address + 1,
+                false => network_address,
+            };
+        (Ipv6Addr::from(first_usable), self.broadcast())
+    }
+}
+
 impl Inspectable for Ipv6Net {
-    fn inspect(&self) -> InspectionResult {
+    fn inspect_with(&self, options: &InspectOptions) -> InspectionResult {
         let cidr = format!("{}/{}", self.addr(), self.prefix_len());
+        let (first_usable, last_usable) = self.usable_range(options.reserve_subnet_router_anycast);
+        let special_purpose = special_purpose(&Cidr::V6(*self));
         InspectionResult::V6(Ipv6InspectionResult {
             cidr,
             address: self.addr().to_string(),
@@ -31,17 +171,44 @@ impl Inspectable for Ipv6Net {
             netmask: self.netmask().to_string(),
             hostmask: self.hostmask().to_string(),
             network: self.network().to_string(),
+            first_usable: first_usable.to_string(),
+            last_usable: last_usable.to_string(),
+            last_address: self.broadcast().to_string(),
             subnet_size: self.subnet_size(),
+            globally_reachable: globally_reachable(&special_purpose),
+            special_purpose,
+            bits: options
+                .bits
+                .then(|| Bits::v6(self.addr().into(), self.prefix_len())),
+        })
+    }
+}
+
+impl Splittable for Ipv6Net {
+    type Subnets = Ipv6Subnets;
+
+    fn split(&self, by: SplitBy) -> Result<Self::Subnets, SplitError> {
+        let current = self.prefix_len();
+        let new = by.prefix_len(current, MAX_IPV6_CIDR_PREFIX_LEN)?;
+        self.subnets(new).map_err(|_| SplitError::InvalidPrefix {
+            new,
+            current,
+            max: MAX_IPV6_CIDR_PREFIX_LEN,
         })
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::inspector::{Inspectable, InspectionResult, ipv6::Ipv6InspectionResult};
+    use crate::inspector::{
+        InspectOptions, Inspectable, InspectionResult, ipv6::Ipv6InspectionResult,
+    };
     use ipnet::Ipv6Net;
+    use std::net::Ipv6Addr;
 
-    use super::SubnetSize;
+    use super::{Ipv6CidrParseError, SubnetSize, UsableRange, parse_ipv6_cidr};
+    use crate::special::SpecialPurpose;
+    use crate::split::{SplitBy, Splittable};
 
     const EXPECTED_IPV6_STR: &str = "2001:db8:1::ab9:c0a8:102";
 
@@ -94,7 +261,19 @@ mod test {
                 netmask: expected_netmask,
                 hostmask: expected_hostmask,
                 network: expected_network,
+                first_usable: "2001:db8:1::".to_string(),
+                last_usable: "2001:db8:1:0:ffff:ffff:ffff:ffff".to_string(),
+                last_address: "2001:db8:1:0:ffff:ffff:ffff:ffff".to_string(),
                 subnet_size: expected_subnet_size,
+                special_purpose: vec![SpecialPurpose {
+                    block: "2001:db8::/32".to_string(),
+                    name: "Documentation".to_string(),
+                    rfc: "RFC 3849".to_string(),
+                    globally_reachable: false,
+                    partial: false,
+                }],
+                globally_reachable: false,
+                bits: None,
             });
 
         // Act
@@ -103,4 +282,147 @@ mod test {
         // Assert
         assert_eq!(actual_inspection_result, expected_inspection_result);
     }
+
+    #[test]
+    fn test_inspect_with_reserved_subnet_router_anycast() {
+        // Arrange
+        let expected_ipv6_cidr: Ipv6Net = format!("{EXPECTED_IPV6_STR}/64").parse().unwrap();
+        let expected_options = InspectOptions {
+            reserve_subnet_router_anycast: true,
+            ..Default::default()
+        };
+
+        // Act
+        let actual_inspection_result: InspectionResult =
+            expected_ipv6_cidr.inspect_with(&expected_options);
+
+        // Assert
+        match actual_inspection_result {
+            InspectionResult::V6(v6) => {
+                assert_eq!(v6.network, "2001:db8:1::");
+                assert_eq!(v6.first_usable, "2001:db8:1::1");
+                assert_eq!(v6.last_usable, "2001:db8:1:0:ffff:ffff:ffff:ffff");
+            }
+            _ => panic!("Expected InspectionResult::V6"),
+        }
+    }
+
+    #[test]
+    fn test_usable_range_point_to_point_keeps_anycast() {
+        // Arrange
+        let expected_ipv6_cidr: Ipv6Net = "2001:db8::/127".parse().unwrap();
+        let expected_first_usable: Ipv6Addr = "2001:db8::".parse().unwrap();
+        let expected_last_usable: Ipv6Addr = "2001:db8::1".parse().unwrap();
+
+        // Act
+        let actual_usable_range: (Ipv6Addr, Ipv6Addr) = expected_ipv6_cidr.usable_range(true);
+
+        // Assert
+        assert_eq!(
+            actual_usable_range,
+            (expected_first_usable, expected_last_usable)
+        );
+    }
+
+    #[test]
+    fn test_split_by_count() {
+        // Arrange
+        let expected_ipv6_cidr: Ipv6Net = "2001:db8:1::/48".parse().unwrap();
+        let expected_last_subnet: Ipv6Net = "2001:db8:1:f000::/52".parse().unwrap();
+
+        // Act
+        let actual_subnets: Vec<Ipv6Net> = expected_ipv6_cidr
+            .split(SplitBy::Count(16))
+            .unwrap()
+            .collect();
+
+        // Assert
+        assert_eq!(actual_subnets.len(), 16);
+        assert_eq!(actual_subnets.last(), Some(&expected_last_subnet));
+    }
+
+    #[test]
+    fn test_split_is_lazy() {
+        // Arrange
+        let expected_ipv6_cidr: Ipv6Net = "2001:db8::/32".parse().unwrap();
+        let expected_first_subnet: Ipv6Net = "2001:db8::/128".parse().unwrap();
+
+        // Act
+        let actual_first_subnet: Option<Ipv6Net> = expected_ipv6_cidr
+            .split(SplitBy::Prefix(128))
+            .unwrap()
+            .next();
+
+        // Assert
+        assert_eq!(actual_first_subnet, Some(expected_first_subnet));
+    }
+
+    #[test]
+    fn test_parse_ipv6_cidr_success() {
+        // Arrange
+        let expected_cidr: Ipv6Net = "::ffff:10.0.0.1/128".parse().unwrap();
+
+        // Act
+        let actual_cidr = parse_ipv6_cidr("::ffff:10.0.0.1/128").unwrap();
+
+        // Assert
+        assert_eq!(actual_cidr, expected_cidr);
+    }
+
+    #[test]
+    fn test_parse_ipv6_cidr_errors_point_at_the_problem() {
+        // Arrange
+        let expected_errors = [
+            (
+                "2001:db8::g/32",
+                Ipv6CidrParseError::InvalidCharacter {
+                    character: 'g',
+                    offset: 10,
+                },
+                10..11,
+            ),
+            (
+                "2001:db8:12345::/48",
+                Ipv6CidrParseError::GroupTooLong {
+                    value: "12345".to_string(),
+                    offset: 9,
+                },
+                9..14,
+            ),
+            (
+                "2001::db8::/32",
+                Ipv6CidrParseError::RepeatedDoubleColon { offset: 9 },
+                9..11,
+            ),
+            (
+                "2001:db8::/129",
+                Ipv6CidrParseError::PrefixTooLarge {
+                    value: "129".to_string(),
+                    offset: 11,
+                },
+                11..14,
+            ),
+            (
+                "2001:db8::",
+                Ipv6CidrParseError::MissingPrefix { offset: 10 },
+                10..10,
+            ),
+            (
+                "1:2:3/64",
+                Ipv6CidrParseError::InvalidAddress {
+                    value: "1:2:3".to_string(),
+                },
+                0..5,
+            ),
+        ];
+
+        for (input, expected_error, expected_span) in expected_errors {
+            // Act
+            let actual_error = parse_ipv6_cidr(input).unwrap_err();
+
+            // Assert
+            assert_eq!(actual_error, expected_error);
+            assert_eq!(actual_error.span(), expected_span);
+        }
+    }
 }
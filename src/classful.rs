@@ -0,0 +1,166 @@
+//! Historic classful addressing ([RFC 791](https://www.rfc-editor.org/rfc/rfc791)), as used before CIDR
+
+use crate::cidr::ipv4::Ipv4Cidr;
+use serde::Serialize;
+use std::fmt::Display;
+use std::net::Ipv4Addr;
+
+/// Address class, decided by the leading bits of an address
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum AddressClass {
+    A,
+    B,
+    C,
+    /// Multicast
+    D,
+    /// Reserved
+    E,
+}
+
+impl AddressClass {
+    fn of(addr: Ipv4Addr) -> Self {
+        match u32::from(addr).leading_ones() {
+            0 => AddressClass::A,
+            1 => AddressClass::B,
+            2 => AddressClass::C,
+            3 => AddressClass::D,
+            _ => AddressClass::E,
+        }
+    }
+
+    /// Prefix length of the default classful mask. `None` for classes D and E, which have no network part
+    pub fn default_prefix_len(&self) -> Option<u8> {
+        match self {
+            AddressClass::A => Some(8),
+            AddressClass::B => Some(16),
+            AddressClass::C => Some(24),
+            AddressClass::D | AddressClass::E => None,
+        }
+    }
+}
+
+impl Display for AddressClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// How a network relates to the classful network of its class
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClassfulBoundary {
+    /// The network is exactly the classful network
+    Classful,
+    /// The network is longer than the default mask
+    Subnet,
+    /// The network is shorter than the default mask
+    Supernet,
+}
+
+impl Display for ClassfulBoundary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClassfulBoundary::Classful => write!(f, "classful"),
+            ClassfulBoundary::Subnet => write!(f, "subnet"),
+            ClassfulBoundary::Supernet => write!(f, "supernet"),
+        }
+    }
+}
+
+/// Classful view of an IPv4 network. Network, mask and boundary are `None` for classes D and E
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Classful {
+    pub class: AddressClass,
+    /// Classful network the network address falls in e.g. 172.16.0.0/16
+    pub network: Option<String>,
+    pub default_mask: Option<String>,
+    pub boundary: Option<ClassfulBoundary>,
+}
+
+impl Ipv4Cidr {
+    /// Classful view of the network, decided by its network address
+    ///
+    /// # Example
+    /// ```
+    /// use cidit::{AddressClass, ClassfulBoundary, Ipv4Cidr};
+    ///
+    /// let cidr: Ipv4Cidr = "172.16.4.0/22".parse().unwrap();
+    ///
+    /// let actual_classful = cidr.classful();
+    ///
+    /// assert_eq!(actual_classful.class, AddressClass::B);
+    /// assert_eq!(actual_classful.network.as_deref(), Some("172.16.0.0/16"));
+    /// assert_eq!(actual_classful.boundary, Some(ClassfulBoundary::Subnet));
+    /// ```
+    pub fn classful(&self) -> Classful {
+        let class = AddressClass::of(self.network());
+        let default = class
+            .default_prefix_len()
+            .map(|prefix| Ipv4Cidr::from_valid_parts(self.network().into(), prefix));
+        Classful {
+            class,
+            network: default
+                .map(|default| format!("{}/{}", default.network(), default.prefix_len())),
+            default_mask: default
+                .map(|default| Ipv4Addr::from(u32::MAX << (32 - default.prefix_len())).to_string()),
+            boundary: default.map(
+                |default| match self.prefix_len().cmp(&default.prefix_len()) {
+                    std::cmp::Ordering::Equal => ClassfulBoundary::Classful,
+                    std::cmp::Ordering::Greater => ClassfulBoundary::Subnet,
+                    std::cmp::Ordering::Less => ClassfulBoundary::Supernet,
+                },
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AddressClass, Classful, ClassfulBoundary};
+    use crate::cidr::ipv4::Ipv4Cidr;
+
+    #[test]
+    fn test_classful() {
+        // Arrange
+        let expected_results = [
+            (
+                "10.1.2.0/24",
+                AddressClass::A,
+                Some("10.0.0.0/8"),
+                Some("255.0.0.0"),
+                Some(ClassfulBoundary::Subnet),
+            ),
+            (
+                "172.16.0.0/16",
+                AddressClass::B,
+                Some("172.16.0.0/16"),
+                Some("255.255.0.0"),
+                Some(ClassfulBoundary::Classful),
+            ),
+            (
+                "192.168.0.0/22",
+                AddressClass::C,
+                Some("192.168.0.0/24"),
+                Some("255.255.255.0"),
+                Some(ClassfulBoundary::Supernet),
+            ),
+            ("224.0.0.1/32", AddressClass::D, None, None, None),
+            ("240.0.0.0/4", AddressClass::E, None, None, None),
+        ];
+
+        for (cidr, class, network, default_mask, boundary) in expected_results {
+            let expected_classful = Classful {
+                class,
+                network: network.map(String::from),
+                default_mask: default_mask.map(String::from),
+                boundary,
+            };
+
+            // Act
+            let actual_classful = cidr.parse::<Ipv4Cidr>().unwrap().classful();
+
+            // Assert
+            assert_eq!(actual_classful, expected_classful, "{cidr}");
+        }
+    }
+}
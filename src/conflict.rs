@@ -0,0 +1,172 @@
+use crate::cidr::Cidr;
+use std::fmt::Display;
+
+/// How the first network of a [`Conflict`] relates to the second one
+///
+/// CIDR blocks are aligned, so two overlapping networks are always either equal or nested
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Relation {
+    /// Both cover exactly the same addresses
+    Equal,
+    /// The first network contains the second one
+    Contains,
+    /// The first network is contained by the second one
+    ContainedBy,
+}
+
+impl Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Relation::Equal => write!(f, "equal"),
+            Relation::Contains => write!(f, "contains"),
+            Relation::ContainedBy => write!(f, "contained_by"),
+        }
+    }
+}
+
+/// Pair of overlapping networks. `first` comes before `second` in the input
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Conflict {
+    pub first: Cidr,
+    pub second: Cidr,
+    pub relation: Relation,
+}
+
+/// Trait that provides the `conflicts` function for lists of networks
+///
+/// # Example
+/// ```
+/// use cidit::{Cidr, Conflict, Conflicting, Relation};
+///
+/// let expected_cidrs: Vec<Cidr> = ["10.0.1.0/24", "10.0.0.0/16", "192.168.0.0/24"]
+///     .iter()
+///     .map(|cidr| cidr.parse().unwrap())
+///     .collect();
+///
+/// let actual_conflicts = expected_cidrs.conflicts();
+///
+/// assert_eq!(actual_conflicts, vec![Conflict {
+///     first: expected_cidrs[0],
+///     second: expected_cidrs[1],
+///     relation: Relation::ContainedBy,
+/// }]);
+/// ```
+pub trait Conflicting {
+    /// Every pair of overlapping networks, ordered by their position in the input
+    fn conflicts(&self) -> Vec<Conflict>;
+}
+
+impl Conflicting for [Cidr] {
+    fn conflicts(&self) -> Vec<Conflict> {
+        // Sorting by start, widest first, puts every network right after all networks containing it
+        let mut sorted: Vec<(u8, u128, u128, usize)> = self
+            .iter()
+            .enumerate()
+            .map(|(index, cidr)| {
+                let (first, last) = cidr.range();
+                (cidr.bits(), first, last, index)
+            })
+            .collect();
+        sorted.sort_unstable_by_key(|&(bits, first, last, index)| {
+            (bits, first, std::cmp::Reverse(last), index)
+        });
+
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+        let mut open: Vec<(u8, u128, usize)> = Vec::new();
+        for (bits, first, last, index) in sorted {
+            open.retain(|&(open_bits, open_last, _)| open_bits == bits && open_last >= first);
+            pairs.extend(
+                open.iter()
+                    .map(|&(_, _, open_index)| (open_index.min(index), open_index.max(index))),
+            );
+            open.push((bits, last, index));
+        }
+        pairs.sort_unstable();
+
+        pairs
+            .into_iter()
+            .map(|(first, second)| {
+                let (first_range, second_range) = (self[first].range(), self[second].range());
+                let relation = if first_range == second_range {
+                    Relation::Equal
+                } else if self[first].is_supernet_of(&self[second]) {
+                    Relation::Contains
+                } else {
+                    Relation::ContainedBy
+                };
+                Conflict {
+                    first: self[first],
+                    second: self[second],
+                    relation,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Conflict, Conflicting, Relation};
+    use crate::cidr::cidrs;
+
+    #[test]
+    fn test_conflicts_nested_and_equal() {
+        // Arrange
+        let expected_cidrs = cidrs(&[
+            "10.0.0.0/16",
+            "192.168.0.0/24",
+            "10.0.5.0/24",
+            "10.0.0.1/16",
+            "172.16.0.0/12",
+        ]);
+        let expected_conflicts = vec![
+            Conflict {
+                first: expected_cidrs[0],
+                second: expected_cidrs[2],
+                relation: Relation::Contains,
+            },
+            Conflict {
+                first: expected_cidrs[0],
+                second: expected_cidrs[3],
+                relation: Relation::Equal,
+            },
+            Conflict {
+                first: expected_cidrs[2],
+                second: expected_cidrs[3],
+                relation: Relation::ContainedBy,
+            },
+        ];
+
+        // Act
+        let actual_conflicts = expected_cidrs.conflicts();
+
+        // Assert
+        assert_eq!(actual_conflicts, expected_conflicts);
+    }
+
+    #[test]
+    fn test_no_conflicts_across_families() {
+        // Arrange
+        let expected_cidrs = cidrs(&["0.0.0.0/0", "::/0", "10.0.0.0/8", "2001:db8::/32"]);
+
+        // Act
+        let actual_conflicts = expected_cidrs.conflicts();
+
+        // Assert
+        assert_eq!(actual_conflicts.len(), 2);
+        assert_eq!(actual_conflicts[0].second, expected_cidrs[2]);
+        assert_eq!(actual_conflicts[1].second, expected_cidrs[3]);
+    }
+
+    #[test]
+    fn test_adjacent_networks_do_not_conflict() {
+        // Arrange
+        let expected_cidrs = cidrs(&["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/23"]);
+
+        // Act
+        let actual_conflicts = expected_cidrs.conflicts();
+
+        // Assert
+        assert!(actual_conflicts.is_empty());
+    }
+}
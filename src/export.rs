@@ -0,0 +1,282 @@
+mod cisco;
+mod juniper;
+
+use cidit::Cidr;
+use clap::ValueEnum;
+use std::fmt::Display;
+use std::net::IpAddr;
+
+/// Device dialect to write config for
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub(crate) enum Vendor {
+    Cisco,
+    Juniper,
+}
+
+/// Kind of config statement to write
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub(crate) enum Statement {
+    Acl,
+    PrefixList,
+    Route,
+}
+
+/// Address family of a network
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Family {
+    V4,
+    V6,
+}
+
+impl Family {
+    pub(crate) fn of(cidr: &Cidr) -> Self {
+        match cidr {
+            Cidr::V4(_) => Family::V4,
+            Cidr::V6(_) => Family::V6,
+        }
+    }
+}
+
+impl Display for Family {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Family::V4 => write!(f, "v4"),
+            Family::V6 => write!(f, "v6"),
+        }
+    }
+}
+
+/// Lines written for each network. `header` is written once, before the first network of a family
+///
+/// Placeholders: `{name}`, `{action}`, `{seq}`, `{cidr}`, `{network}`, `{prefix}`, `{netmask}`,
+/// `{wildcard}` and `{next_hop}`
+pub(crate) struct Template {
+    header: Option<&'static str>,
+    lines: &'static [&'static str],
+}
+
+/// How a vendor words actions and which template it uses for each statement
+pub(crate) struct Dialect {
+    permit: &'static str,
+    deny: &'static str,
+    template: fn(Statement, Family) -> Template,
+}
+
+impl Vendor {
+    fn dialect(&self) -> &'static Dialect {
+        match self {
+            Vendor::Cisco => &cisco::DIALECT,
+            Vendor::Juniper => &juniper::DIALECT,
+        }
+    }
+}
+
+/// Values shared by every exported line
+pub(crate) struct ExportOptions {
+    pub(crate) name: String,
+    pub(crate) deny: bool,
+    pub(crate) next_hop: Option<IpAddr>,
+}
+
+fn render(
+    template: &str,
+    cidr: &Cidr,
+    seq: usize,
+    action: &str,
+    options: &ExportOptions,
+) -> String {
+    let (network, netmask, wildcard) = match cidr {
+        Cidr::V4(v4) => (
+            v4.network().to_string(),
+            v4.netmask().to_string(),
+            v4.hostmask().to_string(),
+        ),
+        Cidr::V6(v6) => (
+            v6.network().to_string(),
+            v6.netmask().to_string(),
+            v6.hostmask().to_string(),
+        ),
+    };
+    let value = |placeholder: &str| match placeholder {
+        "name" => options.name.clone(),
+        "action" => action.to_string(),
+        "seq" => seq.to_string(),
+        "cidr" => format!("{}/{}", network, cidr.prefix_len()),
+        "network" => network.clone(),
+        "prefix" => cidr.prefix_len().to_string(),
+        "netmask" => netmask.clone(),
+        "wildcard" => wildcard.clone(),
+        "next_hop" => options
+            .next_hop
+            .map(|ip| ip.to_string())
+            .unwrap_or_default(),
+        _ => unreachable!("unknown placeholder {{{placeholder}}} in {template}"),
+    };
+
+    // Placeholders are filled in one pass, so values such as --name are written as they are
+    let mut parts = template.split('{');
+    let mut line = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let (placeholder, text) = part
+            .split_once('}')
+            .unwrap_or_else(|| unreachable!("unclosed placeholder in {template}"));
+        line.push_str(&value(placeholder));
+        line.push_str(text);
+    }
+    line
+}
+
+/// Config lines for `cidrs`, IPv4 networks first. Sequence numbers go up by 10 within each family
+pub(crate) fn export(
+    cidrs: &[Cidr],
+    vendor: Vendor,
+    statement: Statement,
+    options: &ExportOptions,
+) -> Result<Vec<String>, String> {
+    let dialect = vendor.dialect();
+    let action = match options.deny {
+        true => dialect.deny,
+        false => dialect.permit,
+    };
+    let mut lines = Vec::new();
+    for family in [Family::V4, Family::V6] {
+        let cidrs: Vec<&Cidr> = cidrs
+            .iter()
+            .filter(|cidr| Family::of(cidr) == family)
+            .collect();
+        if cidrs.is_empty() {
+            continue;
+        }
+        if let (Statement::Route, Some(next_hop)) = (statement, options.next_hop)
+            && next_hop.is_ipv4() != matches!(family, Family::V4)
+        {
+            return Err(format!(
+                "next hop {} is not of the same family as {}",
+                next_hop, cidrs[0]
+            ));
+        }
+        let template = (dialect.template)(statement, family);
+        if let Some(header) = template.header {
+            lines.push(render(header, cidrs[0], 0, action, options));
+        }
+        for (index, cidr) in cidrs.into_iter().enumerate() {
+            for line in template.lines {
+                lines.push(render(line, cidr, (index + 1) * 10, action, options));
+            }
+        }
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExportOptions, Statement, Vendor, export};
+
+    fn options(next_hop: Option<&str>) -> ExportOptions {
+        ExportOptions {
+            name: "EDGE".to_string(),
+            deny: false,
+            next_hop: next_hop.map(|ip| ip.parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_export_cisco() {
+        // Arrange
+        let expected_acl = vec![
+            "ip access-list standard EDGE",
+            " permit 10.0.0.0 0.0.0.255",
+            "ipv6 access-list EDGE",
+            " permit ipv6 2001:db8::/32 any",
+        ];
+        let expected_routes = vec!["ip route 10.0.0.0 255.255.255.0 192.0.2.1"];
+
+        // Act
+        let actual_acl = export(
+            &[
+                "2001:db8::/32".parse().unwrap(),
+                "10.0.0.7/24".parse().unwrap(),
+            ],
+            Vendor::Cisco,
+            Statement::Acl,
+            &options(None),
+        )
+        .unwrap();
+        let actual_routes = export(
+            &["10.0.0.0/24".parse().unwrap()],
+            Vendor::Cisco,
+            Statement::Route,
+            &options(Some("192.0.2.1")),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(actual_acl, expected_acl);
+        assert_eq!(actual_routes, expected_routes);
+    }
+
+    #[test]
+    fn test_export_writes_values_literally() {
+        // Arrange
+        let expected_lines = vec![
+            "ip access-list standard X{network}",
+            " permit 10.0.0.0 0.0.0.255",
+        ];
+
+        // Act
+        let actual_lines = export(
+            &["10.0.0.0/24".parse().unwrap()],
+            Vendor::Cisco,
+            Statement::Acl,
+            &ExportOptions {
+                name: "X{network}".to_string(),
+                deny: false,
+                next_hop: None,
+            },
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(actual_lines, expected_lines);
+    }
+
+    #[test]
+    fn test_export_juniper_prefix_list() {
+        // Arrange
+        let expected_lines = vec![
+            "set policy-options prefix-list EDGE 10.0.0.0/24",
+            "set policy-options prefix-list EDGE 2001:db8::/32",
+        ];
+
+        // Act
+        let actual_lines = export(
+            &[
+                "10.0.0.0/24".parse().unwrap(),
+                "2001:db8::/32".parse().unwrap(),
+            ],
+            Vendor::Juniper,
+            Statement::PrefixList,
+            &options(None),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(actual_lines, expected_lines);
+    }
+
+    #[test]
+    fn test_export_next_hop_of_another_family() {
+        // Arrange
+
+        // Act
+        let actual_result = export(
+            &["2001:db8::/32".parse().unwrap()],
+            Vendor::Cisco,
+            Statement::Route,
+            &options(Some("192.0.2.1")),
+        );
+
+        // Assert
+        assert!(actual_result.is_err());
+    }
+}
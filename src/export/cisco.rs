@@ -0,0 +1,38 @@
+//! Cisco IOS
+
+use super::{Dialect, Family, Statement, Template};
+
+pub(super) const DIALECT: Dialect = Dialect {
+    permit: "permit",
+    deny: "deny",
+    template,
+};
+
+fn template(statement: Statement, family: Family) -> Template {
+    match (statement, family) {
+        (Statement::Acl, Family::V4) => Template {
+            header: Some("ip access-list standard {name}"),
+            lines: &[" {action} {network} {wildcard}"],
+        },
+        (Statement::Acl, Family::V6) => Template {
+            header: Some("ipv6 access-list {name}"),
+            lines: &[" {action} ipv6 {cidr} any"],
+        },
+        (Statement::PrefixList, Family::V4) => Template {
+            header: None,
+            lines: &["ip prefix-list {name} seq {seq} {action} {cidr}"],
+        },
+        (Statement::PrefixList, Family::V6) => Template {
+            header: None,
+            lines: &["ipv6 prefix-list {name} seq {seq} {action} {cidr}"],
+        },
+        (Statement::Route, Family::V4) => Template {
+            header: None,
+            lines: &["ip route {network} {netmask} {next_hop}"],
+        },
+        (Statement::Route, Family::V6) => Template {
+            header: None,
+            lines: &["ipv6 route {cidr} {next_hop}"],
+        },
+    }
+}
@@ -0,0 +1,40 @@
+//! Juniper Junos, as `set` commands
+
+use super::{Dialect, Family, Statement, Template};
+
+pub(super) const DIALECT: Dialect = Dialect {
+    permit: "accept",
+    deny: "discard",
+    template,
+};
+
+fn template(statement: Statement, family: Family) -> Template {
+    match (statement, family) {
+        (Statement::Acl, Family::V4) => Template {
+            header: None,
+            lines: &[
+                "set firewall family inet filter {name} term {seq} from source-address {cidr}",
+                "set firewall family inet filter {name} term {seq} then {action}",
+            ],
+        },
+        (Statement::Acl, Family::V6) => Template {
+            header: None,
+            lines: &[
+                "set firewall family inet6 filter {name} term {seq} from source-address {cidr}",
+                "set firewall family inet6 filter {name} term {seq} then {action}",
+            ],
+        },
+        (Statement::PrefixList, _) => Template {
+            header: None,
+            lines: &["set policy-options prefix-list {name} {cidr}"],
+        },
+        (Statement::Route, Family::V4) => Template {
+            header: None,
+            lines: &["set routing-options static route {cidr} next-hop {next_hop}"],
+        },
+        (Statement::Route, Family::V6) => Template {
+            header: None,
+            lines: &["set routing-options rib inet6.0 static route {cidr} next-hop {next_hop}"],
+        },
+    }
+}
@@ -0,0 +1,234 @@
+use crate::export::Family;
+use cidit::Cidr;
+
+/// Name of the sets, tables and chains the firewall formats write, suffixed with the family where it has one
+const NAME: &str = "cidit";
+
+/// Firewall ruleset formats, each writing IPv4 and IPv6 networks apart
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Firewall {
+    /// `nft -f` table with an interval set per family
+    Nftables,
+    /// `ipset restore` input with a hash:net set per family
+    Ipset,
+    /// `iptables-restore` chain for IPv4 or `ip6tables-restore` chain for IPv6, one family at a time
+    IptablesRestore,
+    /// pf.conf table per family
+    PfTable,
+}
+
+/// Networks of `cidrs` by family, IPv4 first. Only networks of `family` are kept when it is given
+fn families(cidrs: &[Cidr], family: Option<Family>) -> [(Family, Vec<String>); 2] {
+    let networks = |of: Family| {
+        cidrs
+            .iter()
+            .filter(|cidr| Family::of(cidr) == of && family.is_none_or(|family| family == of))
+            .map(|cidr| match cidr {
+                Cidr::V4(v4) => format!("{}/{}", v4.network(), v4.prefix_len()),
+                Cidr::V6(v6) => format!("{}/{}", v6.network(), v6.prefix_len()),
+            })
+            .collect()
+    };
+    [
+        (Family::V4, networks(Family::V4)),
+        (Family::V6, networks(Family::V6)),
+    ]
+}
+
+fn nftables(families: [(Family, Vec<String>); 2]) -> Vec<String> {
+    let mut lines = vec![format!("table inet {NAME} {{")];
+    for (family, networks) in families {
+        if networks.is_empty() {
+            continue;
+        }
+        let address_type = match family {
+            Family::V4 => "ipv4_addr",
+            Family::V6 => "ipv6_addr",
+        };
+        lines.extend([
+            format!("\tset {NAME}_{family} {{"),
+            format!("\t\ttype {address_type}"),
+            "\t\tflags interval".to_string(),
+            "\t\tauto-merge".to_string(),
+            format!("\t\telements = {{ {} }}", networks.join(", ")),
+            "\t}".to_string(),
+        ]);
+    }
+    lines.push("}".to_string());
+    lines
+}
+
+fn ipset(families: [(Family, Vec<String>); 2]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (family, networks) in families {
+        if networks.is_empty() {
+            continue;
+        }
+        let ipset_family = match family {
+            Family::V4 => "inet",
+            Family::V6 => "inet6",
+        };
+        lines.push(format!(
+            "create {NAME}_{family} hash:net family {ipset_family}"
+        ));
+        for network in networks {
+            // hash:net cannot store a /0, so the whole address space is added as its two halves
+            let halves = match network.as_str() {
+                "0.0.0.0/0" => vec!["0.0.0.0/1".to_string(), "128.0.0.0/1".to_string()],
+                "::/0" => vec!["::/1".to_string(), "8000::/1".to_string()],
+                _ => vec![network],
+            };
+            lines.extend(
+                halves
+                    .into_iter()
+                    .map(|network| format!("add {NAME}_{family} {network}")),
+            );
+        }
+    }
+    lines
+}
+
+/// `iptables-restore` and `ip6tables-restore` each read a single family, so only one may have networks
+fn iptables_restore(families: [(Family, Vec<String>); 2]) -> Result<Vec<String>, String> {
+    let chain = NAME.to_uppercase();
+    let mut families = families
+        .into_iter()
+        .filter(|(_, networks)| !networks.is_empty());
+    let Some((family, networks)) = families.next() else {
+        return Ok(Vec::new());
+    };
+    if families.next().is_some() {
+        return Err(
+            "iptables-restore takes one family at a time, pick IPv4 or IPv6 networks with --family"
+                .to_string(),
+        );
+    }
+    let restore = match family {
+        Family::V4 => "iptables-restore",
+        Family::V6 => "ip6tables-restore",
+    };
+    let mut lines = vec![
+        format!("# {restore}"),
+        "*filter".to_string(),
+        format!(":{chain} - [0:0]"),
+    ];
+    lines.extend(
+        networks
+            .into_iter()
+            .map(|network| format!("-A {chain} -s {network} -j ACCEPT")),
+    );
+    lines.push("COMMIT".to_string());
+    Ok(lines)
+}
+
+fn pf_table(families: [(Family, Vec<String>); 2]) -> Vec<String> {
+    families
+        .into_iter()
+        .filter(|(_, networks)| !networks.is_empty())
+        .map(|(family, networks)| {
+            format!(
+                "table <{NAME}_{family}> persist {{ {} }}",
+                networks.join(", ")
+            )
+        })
+        .collect()
+}
+
+/// Ruleset lines for the networks of `cidrs` in `family`, or in both families when it is `None`.
+/// Families without networks are left out
+pub(crate) fn render(
+    firewall: Firewall,
+    cidrs: &[Cidr],
+    family: Option<Family>,
+) -> Result<Vec<String>, String> {
+    let families = families(cidrs, family);
+    match firewall {
+        Firewall::Nftables => Ok(nftables(families)),
+        Firewall::Ipset => Ok(ipset(families)),
+        Firewall::IptablesRestore => iptables_restore(families),
+        Firewall::PfTable => Ok(pf_table(families)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Family, Firewall, render};
+    use cidit::Cidr;
+
+    #[test]
+    fn test_render_golden_files() {
+        // Arrange
+        let expected_cidrs: [Cidr; 4] = [
+            "10.0.0.7/24".parse().unwrap(),
+            "2001:db8::/32".parse().unwrap(),
+            "192.168.0.0/16".parse().unwrap(),
+            "0.0.0.0/0".parse().unwrap(),
+        ];
+        let expected_rulesets = [
+            (
+                Firewall::Nftables,
+                None,
+                include_str!("firewall/testdata/nftables.txt"),
+            ),
+            (
+                Firewall::Ipset,
+                None,
+                include_str!("firewall/testdata/ipset.txt"),
+            ),
+            (
+                Firewall::IptablesRestore,
+                Some(Family::V4),
+                include_str!("firewall/testdata/iptables-restore-v4.txt"),
+            ),
+            (
+                Firewall::IptablesRestore,
+                Some(Family::V6),
+                include_str!("firewall/testdata/iptables-restore-v6.txt"),
+            ),
+            (
+                Firewall::PfTable,
+                None,
+                include_str!("firewall/testdata/pf-table.txt"),
+            ),
+        ];
+
+        for (firewall, family, expected_ruleset) in expected_rulesets {
+            // Act
+            let actual_ruleset = render(firewall, &expected_cidrs, family)
+                .unwrap()
+                .join("\n")
+                + "\n";
+
+            // Assert
+            assert_eq!(actual_ruleset, expected_ruleset, "{firewall:?} {family:?}");
+        }
+    }
+
+    #[test]
+    fn test_render_leaves_out_empty_families() {
+        // Arrange
+        let expected_ruleset = vec!["table <cidit_v6> persist { 2001:db8::/32 }"];
+
+        // Act
+        let actual_ruleset =
+            render(Firewall::PfTable, &["2001:db8::/32".parse().unwrap()], None).unwrap();
+
+        // Assert
+        assert_eq!(actual_ruleset, expected_ruleset);
+    }
+
+    #[test]
+    fn test_render_iptables_restore_needs_one_family() {
+        // Arrange
+        let expected_cidrs: [Cidr; 2] = [
+            "10.0.0.0/24".parse().unwrap(),
+            "2001:db8::/32".parse().unwrap(),
+        ];
+
+        // Act
+        let actual_result = render(Firewall::IptablesRestore, &expected_cidrs, None);
+
+        // Assert
+        assert!(actual_result.is_err());
+    }
+}
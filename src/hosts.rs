@@ -0,0 +1,383 @@
+//! Lazy iteration over the usable addresses of a network
+
+use crate::cidr::Cidr;
+use crate::cidr::ipv4::{Ipv4Cidr, Network};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Inclusive range of addresses widened to `u128`, walked from the start
+#[derive(Debug, Clone)]
+struct AddressRange {
+    next: u128,
+    last: u128,
+    done: bool,
+}
+
+impl AddressRange {
+    fn new((first, last): (u128, u128)) -> Self {
+        AddressRange {
+            next: first,
+            last,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for AddressRange {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let address = self.next;
+        match address == self.last {
+            true => self.done = true,
+            false => self.next += 1,
+        }
+        Some(address)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.done || n as u128 > self.last - self.next {
+            self.done = true;
+            return None;
+        }
+        self.next += n as u128;
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        match (self.last - self.next)
+            .checked_add(1)
+            .and_then(|remaining| usize::try_from(remaining).ok())
+        {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl DoubleEndedIterator for AddressRange {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let address = self.last;
+        match address == self.next {
+            true => self.done = true,
+            false => self.last -= 1,
+        }
+        Some(address)
+    }
+}
+
+/// Lazy iterator over the usable addresses of an [`Ipv4Cidr`]
+#[derive(Debug, Clone)]
+pub struct Ipv4Hosts(AddressRange);
+
+impl Iterator for Ipv4Hosts {
+    type Item = Ipv4Addr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|address| Ipv4Addr::from(address as u32))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.0.nth(n).map(|address| Ipv4Addr::from(address as u32))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Ipv4Hosts {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0
+            .next_back()
+            .map(|address| Ipv4Addr::from(address as u32))
+    }
+}
+
+/// Lazy iterator over the usable addresses of a [`Cidr`]
+#[derive(Debug, Clone)]
+pub struct Hosts {
+    range: AddressRange,
+    bits: u8,
+}
+
+fn ip_addr(address: u128, bits: u8) -> IpAddr {
+    match bits {
+        32 => IpAddr::V4(Ipv4Addr::from(address as u32)),
+        _ => IpAddr::V6(Ipv6Addr::from(address)),
+    }
+}
+
+impl Iterator for Hosts {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|address| ip_addr(address, self.bits))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth(n).map(|address| ip_addr(address, self.bits))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Hosts {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range
+            .next_back()
+            .map(|address| ip_addr(address, self.bits))
+    }
+}
+
+/// SplitMix64, small and good enough for sampling addresses. Not for anything security related
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_u128(&mut self) -> u128 {
+        u128::from(self.next_u64()) << 64 | u128::from(self.next_u64())
+    }
+
+    /// Uniform in `low..=high`. Draws that would favour some values are rejected
+    fn between(&mut self, low: u128, high: u128) -> u128 {
+        let width = high - low;
+        if width == u128::MAX {
+            return self.next_u128();
+        }
+        let range = width + 1;
+        let limit = u128::MAX / range * range;
+        loop {
+            let draw = self.next_u128();
+            if draw < limit {
+                return low + draw % range;
+            }
+        }
+    }
+}
+
+/// Lazy iterator over the usable addresses of a [`Cidr`] in random order, each address once
+///
+/// Runs a Fisher-Yates shuffle that only keeps the swapped positions, so memory grows with the addresses drawn,
+/// not with the size of the network
+#[derive(Debug, Clone)]
+pub struct RandomHosts {
+    first: u128,
+    last_index: u128,
+    drawn: u128,
+    done: bool,
+    swapped: HashMap<u128, u128>,
+    rng: SplitMix64,
+    bits: u8,
+}
+
+impl Iterator for RandomHosts {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let index = self.rng.between(self.drawn, self.last_index);
+        let current = self.swapped.remove(&self.drawn).unwrap_or(self.drawn);
+        let drawn = match index == self.drawn {
+            true => current,
+            false => self.swapped.insert(index, current).unwrap_or(index),
+        };
+        match self.drawn == self.last_index {
+            true => self.done = true,
+            false => self.drawn += 1,
+        }
+        Some(ip_addr(self.first + drawn, self.bits))
+    }
+}
+
+impl Ipv4Cidr {
+    /// Usable addresses, without the network and broadcast addresses of networks that have them
+    ///
+    /// # Example
+    /// ```
+    /// use cidit::Ipv4Cidr;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let cidr: Ipv4Cidr = "10.0.0.0/30".parse().unwrap();
+    ///
+    /// let actual_hosts: Vec<Ipv4Addr> = cidr.hosts().collect();
+    ///
+    /// assert_eq!(actual_hosts, vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)]);
+    /// ```
+    pub fn hosts(&self) -> Ipv4Hosts {
+        let (first, last) = self.get_usable_range();
+        Ipv4Hosts(AddressRange::new((first.into(), last.into())))
+    }
+}
+
+impl Cidr {
+    fn usable_range(&self) -> (u128, u128) {
+        match self {
+            Cidr::V4(v4) => {
+                let (first, last) = v4.get_usable_range();
+                (first.into(), last.into())
+            }
+            Cidr::V6(_) => self.range(),
+        }
+    }
+
+    /// Usable addresses. Every address of an IPv6 network is usable
+    pub fn hosts(&self) -> Hosts {
+        Hosts {
+            range: AddressRange::new(self.usable_range()),
+            bits: self.bits(),
+        }
+    }
+
+    /// Usable addresses in random order, each address once. The same `seed` gives the same order
+    pub fn random_hosts(&self, seed: u64) -> RandomHosts {
+        let (first, last) = self.usable_range();
+        RandomHosts {
+            first,
+            last_index: last - first,
+            drawn: 0,
+            done: false,
+            swapped: HashMap::new(),
+            rng: SplitMix64(seed),
+            bits: self.bits(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cidr::Cidr;
+    use std::collections::HashSet;
+    use std::net::IpAddr;
+
+    #[test]
+    fn test_hosts() {
+        // Arrange
+        let expected_hosts = [
+            ("10.0.0.0/30", vec!["10.0.0.1", "10.0.0.2"]),
+            ("10.0.0.0/31", vec!["10.0.0.0", "10.0.0.1"]),
+            ("10.0.0.9/32", vec!["10.0.0.9"]),
+            ("2001:db8::/127", vec!["2001:db8::", "2001:db8::1"]),
+        ];
+
+        for (cidr, expected_hosts) in expected_hosts {
+            // Act
+            let actual_hosts: Vec<String> = cidr
+                .parse::<Cidr>()
+                .unwrap()
+                .hosts()
+                .map(|host| host.to_string())
+                .collect();
+
+            // Assert
+            assert_eq!(actual_hosts, expected_hosts, "{cidr}");
+        }
+    }
+
+    #[test]
+    fn test_hosts_skip_and_step_are_lazy() {
+        // Arrange
+        let expected_cidr: Cidr = "::/0".parse().unwrap();
+        let expected_hosts: Vec<IpAddr> = vec![
+            "::ffff:ffff:ffff:ffff".parse().unwrap(),
+            "::1:0:ffff:ffff:ffff".parse().unwrap(),
+        ];
+
+        // Act
+        let actual_hosts: Vec<IpAddr> = expected_cidr
+            .hosts()
+            .skip(u64::MAX as usize)
+            .step_by(1 << 48)
+            .take(2)
+            .collect();
+
+        // Assert
+        assert_eq!(actual_hosts, expected_hosts);
+    }
+
+    #[test]
+    fn test_hosts_nth_past_the_end() {
+        // Arrange
+        let expected_cidr: Cidr = "10.0.0.0/30".parse().unwrap();
+        let mut expected_hosts = expected_cidr.hosts();
+
+        // Act
+        let actual_host = expected_hosts.nth(2);
+
+        // Assert
+        assert_eq!(actual_host, None);
+        assert_eq!(expected_hosts.next(), None);
+    }
+
+    #[test]
+    fn test_hosts_from_the_end() {
+        // Arrange
+        let expected_cidr: Cidr = "10.0.0.0/24".parse().unwrap();
+        let expected_hosts: Vec<IpAddr> = vec![
+            "10.0.0.254".parse().unwrap(),
+            "10.0.0.253".parse().unwrap(),
+            "10.0.0.252".parse().unwrap(),
+        ];
+
+        // Act
+        let actual_hosts: Vec<IpAddr> = expected_cidr.hosts().rev().take(3).collect();
+
+        // Assert
+        assert_eq!(actual_hosts, expected_hosts);
+    }
+
+    #[test]
+    fn test_random_hosts_draw_each_address_once() {
+        // Arrange
+        let expected_cidr: Cidr = "10.0.0.0/28".parse().unwrap();
+        let expected_hosts: HashSet<IpAddr> = expected_cidr.hosts().collect();
+
+        // Act
+        let actual_hosts: Vec<IpAddr> = expected_cidr.random_hosts(7).collect();
+
+        // Assert
+        assert_eq!(actual_hosts.len(), expected_hosts.len());
+        assert_eq!(
+            actual_hosts.iter().copied().collect::<HashSet<_>>(),
+            expected_hosts
+        );
+        assert_ne!(actual_hosts, expected_cidr.hosts().collect::<Vec<_>>());
+        assert_eq!(
+            actual_hosts,
+            expected_cidr.random_hosts(7).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_random_hosts_of_a_huge_network() {
+        // Arrange
+        let expected_cidr: Cidr = "::/0".parse().unwrap();
+
+        // Act
+        let actual_hosts: HashSet<IpAddr> = expected_cidr.random_hosts(1).take(1000).collect();
+
+        // Assert
+        assert_eq!(actual_hosts.len(), 1000);
+    }
+}
@@ -12,15 +12,29 @@ pub enum InspectionResult {
     V6(Ipv6InspectionResult),
 }
 
+/// Options that change how an [`Inspectable`] computes its [`InspectionResult`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InspectOptions {
+    /// Treat the IPv6 Subnet-Router anycast address (RFC 4291, section 2.6.1) as reserved,
+    /// so it is not reported as the first usable address
+    pub reserve_subnet_router_anycast: bool,
+    /// Report the historic classful network of IPv4 networks
+    pub classful: bool,
+    /// Also render the address, masks and bounds in binary, hex and integer form
+    pub bits: bool,
+}
+
 /// Trait that provides `inspect` function
 ///
-/// Bring this trait into the scope to get an InspectionResult
+/// Bring this trait into the scope to get an InspectionResult.
+/// Use `inspect_with` to pass [`InspectOptions`]
 ///
 /// # Example
 /// ```
 /// use cidit::InspectionResult;
 /// use cidit::Ipv4InspectionResult;
 /// use cidit::Ipv4Cidr;
+/// use cidit::SpecialPurpose;
 ///
 /// use cidit::Inspectable;
 ///
@@ -29,10 +43,24 @@ pub enum InspectionResult {
 ///                cidr: expected_ipv4_cidr_string.clone(),
 ///                first_usable: String::from("10.22.135.1"),
 ///                last_usable: String::from("10.22.135.254"),
-///                broadcast: String::from("10.22.135.255"),
+///                broadcast: Some(String::from("10.22.135.255")),
 ///                network: String::from("10.22.135.0"),
 ///                address: String::from("10.22.135.144"),
 ///                prefix_length: 24,
+///                netmask: String::from("255.255.255.0"),
+///                hostmask: String::from("0.0.0.255"),
+///                total_addresses: 256,
+///                usable_addresses: 254,
+///                special_purpose: vec![SpecialPurpose {
+///                    block: String::from("10.0.0.0/8"),
+///                    name: String::from("Private-Use"),
+///                    rfc: String::from("RFC 1918"),
+///                    globally_reachable: false,
+///                    partial: false,
+///                }],
+///                globally_reachable: false,
+///                classful: None,
+///                bits: None,
 ///                });
 /// let expected_ipv4_cidr: Ipv4Cidr = expected_ipv4_cidr_string.parse().unwrap();
 ///
@@ -41,5 +69,9 @@ pub enum InspectionResult {
 /// assert_eq!(actual_inspection_result, expected_inspection_result);
 /// ```
 pub trait Inspectable {
-    fn inspect(&self) -> InspectionResult;
+    fn inspect(&self) -> InspectionResult {
+        self.inspect_with(&InspectOptions::default())
+    }
+
+    fn inspect_with(&self, options: &InspectOptions) -> InspectionResult;
 }
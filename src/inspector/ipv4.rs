@@ -1,3 +1,6 @@
+use crate::bits::Bits;
+use crate::classful::Classful;
+use crate::special::SpecialPurpose;
 use serde::Serialize;
 
 /// Inspection result for IPv4
@@ -9,5 +12,20 @@ pub struct Ipv4InspectionResult {
     pub first_usable: String,
     pub last_usable: String,
     pub network: String,
-    pub broadcast: String,
+    /// `None` for /31 and /32 networks, which have no broadcast address (RFC 3021)
+    pub broadcast: Option<String>,
+    pub netmask: String,
+    pub hostmask: String,
+    pub total_addresses: u64,
+    pub usable_addresses: u64,
+    /// IANA special-purpose blocks the network falls in, widest first
+    pub special_purpose: Vec<SpecialPurpose>,
+    /// `false` if the most specific special-purpose block is not globally reachable
+    pub globally_reachable: bool,
+    /// Historic classful view, only with [`InspectOptions::classful`](crate::InspectOptions::classful)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classful: Option<Classful>,
+    /// Binary, hex and integer renderings, only with [`InspectOptions::bits`](crate::InspectOptions::bits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bits: Option<Bits>,
 }
@@ -1,3 +1,5 @@
+use crate::bits::Bits;
+use crate::special::SpecialPurpose;
 use serde::Serialize;
 
 /// Inspection result for IPv6
@@ -9,5 +11,16 @@ pub struct Ipv6InspectionResult {
     pub netmask: String,
     pub hostmask: String,
     pub network: String,
+    pub first_usable: String,
+    pub last_usable: String,
+    /// Last address of the range. IPv6 has no broadcast, so this is also the last usable address
+    pub last_address: String,
     pub subnet_size: String,
+    /// IANA special-purpose blocks the network falls in, widest first
+    pub special_purpose: Vec<SpecialPurpose>,
+    /// `false` if the most specific special-purpose block is not globally reachable
+    pub globally_reachable: bool,
+    /// Binary, hex and integer renderings, only with [`InspectOptions::bits`](crate::InspectOptions::bits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bits: Option<Bits>,
 }
@@ -1,5 +1,6 @@
 use std::str::FromStr;
 use std::fmt::Display;
+use std::net::Ipv4Addr;
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum IpParseError {
@@ -28,6 +29,12 @@ impl From<u32> for IPv4 {
     }
 }
 
+impl From<IPv4> for Ipv4Addr {
+    fn from(value: IPv4) -> Ipv4Addr {
+        Ipv4Addr::from(value.address)
+    }
+}
+
 impl FromStr for IPv4 {
     type Err = IpParseError;
 
@@ -0,0 +1,225 @@
+use crate::cidr::Cidr;
+use crate::range::{
+    IPV4_BITS, IPV6_BITS, block_size_minus_one, intersect_ranges, merge_ranges, range_to_blocks,
+    subtract_ranges,
+};
+
+/// A set of IPv4 and IPv6 addresses built from networks
+///
+/// Addresses are kept as sorted, merged ranges, so every operation returns the
+/// minimal list of networks through [`IpSet::cidrs`]
+///
+/// # Example
+/// ```
+/// use cidit::{Cidr, IpSet};
+///
+/// let expected_pool: IpSet = ["10.0.0.0/8".parse::<Cidr>().unwrap()].into_iter().collect();
+/// let expected_allocated: IpSet = ["10.0.0.0/9", "10.128.0.0/10"]
+///     .iter()
+///     .map(|cidr| cidr.parse::<Cidr>().unwrap())
+///     .collect();
+///
+/// let actual_free: Vec<Cidr> = expected_pool.difference(&expected_allocated).cidrs();
+///
+/// assert_eq!(actual_free, vec!["10.192.0.0/10".parse().unwrap()]);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IpSet {
+    v4: Vec<(u128, u128)>,
+    v6: Vec<(u128, u128)>,
+}
+
+impl IpSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set of every IPv4 and IPv6 address
+    pub fn full() -> Self {
+        Self {
+            v4: vec![(0, block_size_minus_one(IPV4_BITS))],
+            v6: vec![(0, block_size_minus_one(IPV6_BITS))],
+        }
+    }
+
+    pub fn insert(&mut self, cidr: Cidr) {
+        let family = self.family_mut(cidr.bits());
+        family.push(cidr.range());
+        *family = merge_ranges(std::mem::take(family));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.v4.is_empty() && self.v6.is_empty()
+    }
+
+    pub fn union(&self, other: &IpSet) -> IpSet {
+        self.combine(other, |a, b| merge_ranges([a, b].concat()))
+    }
+
+    pub fn intersection(&self, other: &IpSet) -> IpSet {
+        self.combine(other, intersect_ranges)
+    }
+
+    pub fn difference(&self, other: &IpSet) -> IpSet {
+        self.combine(other, subtract_ranges)
+    }
+
+    /// Every IPv4 and IPv6 address that is not in the set
+    pub fn complement(&self) -> IpSet {
+        IpSet::full().difference(self)
+    }
+
+    /// Minimal list of networks covering the set. IPv4 networks come first, then IPv6
+    pub fn cidrs(&self) -> Vec<Cidr> {
+        [(IPV4_BITS, &self.v4), (IPV6_BITS, &self.v6)]
+            .into_iter()
+            .flat_map(|(bits, ranges)| {
+                ranges
+                    .iter()
+                    .flat_map(move |&(start, end)| range_to_blocks(start, end, bits))
+                    .map(move |(start, prefix)| Cidr::from_block(start, prefix, bits))
+            })
+            .collect()
+    }
+
+    /// Sorted, merged address ranges of one family
+    pub(crate) fn ranges(&self, bits: u8) -> &[(u128, u128)] {
+        match bits {
+            IPV4_BITS => &self.v4,
+            _ => &self.v6,
+        }
+    }
+
+    fn family_mut(&mut self, bits: u8) -> &mut Vec<(u128, u128)> {
+        match bits {
+            IPV4_BITS => &mut self.v4,
+            _ => &mut self.v6,
+        }
+    }
+
+    fn combine(
+        &self,
+        other: &IpSet,
+        operation: impl Fn(&[(u128, u128)], &[(u128, u128)]) -> Vec<(u128, u128)>,
+    ) -> IpSet {
+        IpSet {
+            v4: operation(&self.v4, &other.v4),
+            v6: operation(&self.v6, &other.v6),
+        }
+    }
+}
+
+impl From<Cidr> for IpSet {
+    fn from(value: Cidr) -> Self {
+        let mut set = IpSet::new();
+        set.insert(value);
+        set
+    }
+}
+
+impl FromIterator<Cidr> for IpSet {
+    fn from_iter<T: IntoIterator<Item = Cidr>>(iter: T) -> Self {
+        let mut set = IpSet::new();
+        for cidr in iter {
+            set.family_mut(cidr.bits()).push(cidr.range());
+        }
+        set.v4 = merge_ranges(set.v4);
+        set.v6 = merge_ranges(set.v6);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cidr::{cidrs, ip_set};
+
+    #[test]
+    fn test_insert_merges() {
+        // Arrange
+        let mut expected_set = ip_set(&["10.0.0.0/24"]);
+
+        // Act
+        expected_set.insert("10.0.1.0/24".parse().unwrap());
+
+        // Assert
+        assert_eq!(expected_set.cidrs(), cidrs(&["10.0.0.0/23"]));
+    }
+
+    #[test]
+    fn test_union() {
+        // Arrange
+        let expected_a = ip_set(&["10.0.0.0/24", "2001:db8::/33"]);
+        let expected_b = ip_set(&["10.0.1.0/24", "2001:db8:8000::/33"]);
+
+        // Act
+        let actual_union = expected_a.union(&expected_b);
+
+        // Assert
+        assert_eq!(
+            actual_union.cidrs(),
+            cidrs(&["10.0.0.0/23", "2001:db8::/32"])
+        );
+    }
+
+    #[test]
+    fn test_intersection() {
+        // Arrange
+        let expected_a = ip_set(&["10.0.0.0/16", "2001:db8::/32"]);
+        let expected_b = ip_set(&["10.0.5.0/24", "10.1.0.0/24", "192.168.0.0/16"]);
+
+        // Act
+        let actual_intersection = expected_a.intersection(&expected_b);
+
+        // Assert
+        assert_eq!(actual_intersection.cidrs(), cidrs(&["10.0.5.0/24"]));
+    }
+
+    #[test]
+    fn test_difference() {
+        // Arrange
+        let expected_pool = ip_set(&["10.0.0.0/8"]);
+        let expected_allocated = ip_set(&["10.0.0.0/16", "10.128.0.0/9"]);
+
+        // Act
+        let actual_free = expected_pool.difference(&expected_allocated);
+
+        // Assert
+        assert_eq!(
+            actual_free.cidrs(),
+            cidrs(&[
+                "10.1.0.0/16",
+                "10.2.0.0/15",
+                "10.4.0.0/14",
+                "10.8.0.0/13",
+                "10.16.0.0/12",
+                "10.32.0.0/11",
+                "10.64.0.0/10",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_complement() {
+        // Arrange
+        let expected_set = ip_set(&["128.0.0.0/1", "::/1"]);
+
+        // Act
+        let actual_complement = expected_set.complement();
+
+        // Assert
+        assert_eq!(actual_complement.cidrs(), cidrs(&["0.0.0.0/1", "8000::/1"]));
+    }
+
+    #[test]
+    fn test_empty() {
+        // Arrange
+        let expected_set = ip_set(&["10.0.0.0/8"]);
+
+        // Act
+        let actual_difference = expected_set.difference(&ip_set(&["0.0.0.0/0"]));
+
+        // Assert
+        assert!(actual_difference.is_empty());
+        assert!(actual_difference.cidrs().is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+use crate::cidr::ipv4::Ipv4Cidr;
+use crate::cidr::{Cidr, CidrParseError};
+use crate::range::range_to_blocks;
+use std::fmt::Display;
+use std::net::Ipv4Addr;
+use std::ops::Range;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Why a string could not be read even leniently. Offsets are byte offsets into the parsed string
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum LenientParseError {
+    /// A CIDR or address that does not parse, starting at `offset`
+    #[error("{error}")]
+    Cidr {
+        error: CidrParseError,
+        offset: usize,
+    },
+    #[error("'{value}' is not a netmask")]
+    InvalidNetmask { value: String, offset: usize },
+    #[error("netmask {value} is not contiguous")]
+    NonContiguousNetmask { value: String, offset: usize },
+    #[error("range mixes IPv4 and IPv6 addresses")]
+    MixedRange { length: usize },
+    #[error("range starts after it ends")]
+    ReversedRange { length: usize },
+}
+
+impl LenientParseError {
+    /// Bytes of the input the error points at. Empty when something is missing at its start
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            LenientParseError::Cidr { error, offset } => {
+                let span = error.span();
+                span.start + offset..span.end + offset
+            }
+            LenientParseError::InvalidNetmask { value, offset }
+            | LenientParseError::NonContiguousNetmask { value, offset } => {
+                *offset..*offset + value.len()
+            }
+            LenientParseError::MixedRange { length }
+            | LenientParseError::ReversedRange { length } => 0..*length,
+        }
+    }
+
+    /// What was expected at the span
+    pub fn expected(&self) -> String {
+        match self {
+            LenientParseError::Cidr { error, .. } => error.expected(),
+            LenientParseError::InvalidNetmask { .. } => "a netmask e.g. 255.255.255.0".to_string(),
+            LenientParseError::NonContiguousNetmask { .. } => {
+                "ones followed by zeros e.g. 255.255.240.0".to_string()
+            }
+            LenientParseError::MixedRange { .. } => "two addresses of the same family".to_string(),
+            LenientParseError::ReversedRange { .. } => "the lower address first".to_string(),
+        }
+    }
+}
+
+impl From<CidrParseError> for LenientParseError {
+    fn from(error: CidrParseError) -> Self {
+        LenientParseError::Cidr { error, offset: 0 }
+    }
+}
+
+/// How a [`LenientCidr`] input was turned into CIDRs
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Normalization {
+    /// The input already was a CIDR
+    None,
+    /// A bare address e.g. `10.0.0.5`, read as a /32 or /128
+    BareAddress,
+    /// An IPv4 address with a dotted netmask e.g. `10.0.0.0 255.255.255.0` or `10.0.0.0/255.255.255.0`
+    Netmask,
+    /// An inclusive address range e.g. `10.0.0.1-10.0.0.77`, decomposed into the minimal list of CIDRs
+    Range,
+}
+
+impl Display for Normalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Normalization::None => write!(f, "none"),
+            Normalization::BareAddress => write!(f, "bare address"),
+            Normalization::Netmask => write!(f, "netmask"),
+            Normalization::Range => write!(f, "range"),
+        }
+    }
+}
+
+/// CIDRs read from the forms people usually paste, not only from CIDR notation
+///
+/// # Example
+/// ```
+/// use cidit::{Cidr, LenientCidr, Normalization};
+///
+/// let actual_range: LenientCidr = "10.0.0.1-10.0.0.6".parse().unwrap();
+/// let actual_netmask: LenientCidr = "10.0.0.0 255.255.255.0".parse().unwrap();
+///
+/// assert_eq!(actual_range.normalization, Normalization::Range);
+/// assert_eq!(actual_range.cidrs.len(), 4);
+/// assert_eq!(actual_netmask.cidrs, vec!["10.0.0.0/24".parse::<Cidr>().unwrap()]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LenientCidr {
+    pub cidrs: Vec<Cidr>,
+    pub normalization: Normalization,
+}
+
+/// Single host network of a bare address found at `offset` of the input
+fn parse_address(address: &str, offset: usize) -> Result<Cidr, LenientParseError> {
+    let max_prefix = match address.contains(':') {
+        true => 128,
+        false => 32,
+    };
+    format!("{address}/{max_prefix}")
+        .parse()
+        .map_err(|error| LenientParseError::Cidr { error, offset })
+}
+
+fn parse_range(s: &str, start: &str, end: &str) -> Result<LenientCidr, LenientParseError> {
+    let end_offset = s.len() - end.len();
+    let (first, last) = (parse_address(start, 0)?, parse_address(end, end_offset)?);
+    if first.bits() != last.bits() {
+        return Err(LenientParseError::MixedRange { length: s.len() });
+    }
+    let bits = first.bits();
+    let (first, last) = (first.range().0, last.range().0);
+    if first > last {
+        return Err(LenientParseError::ReversedRange { length: s.len() });
+    }
+    Ok(LenientCidr {
+        cidrs: range_to_blocks(first, last, bits)
+            .into_iter()
+            .map(|(block_start, prefix)| Cidr::from_block(block_start, prefix, bits))
+            .collect(),
+        normalization: Normalization::Range,
+    })
+}
+
+fn parse_netmask(s: &str, address: &str, netmask: &str) -> Result<LenientCidr, LenientParseError> {
+    let address: Ipv4Cidr =
+        format!("{address}/32")
+            .parse()
+            .map_err(|error| LenientParseError::Cidr {
+                error: CidrParseError::V4(error),
+                offset: 0,
+            })?;
+    let offset = s.len() - netmask.len();
+    let mask: u32 = netmask
+        .parse::<Ipv4Addr>()
+        .map_err(|_| LenientParseError::InvalidNetmask {
+            value: netmask.to_string(),
+            offset,
+        })?
+        .into();
+    if mask.leading_ones() + mask.trailing_zeros() != 32 {
+        return Err(LenientParseError::NonContiguousNetmask {
+            value: netmask.to_string(),
+            offset,
+        });
+    }
+    Ok(LenientCidr {
+        cidrs: vec![Cidr::V4(Ipv4Cidr::from_valid_parts(
+            address.addr().into(),
+            mask.leading_ones() as u8,
+        ))],
+        normalization: Normalization::Netmask,
+    })
+}
+
+impl FromStr for LenientCidr {
+    type Err = LenientParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((start, end)) = s.split_once('-') {
+            return parse_range(s, start.trim_end(), end.trim_start());
+        }
+        if let Some((address, netmask)) = s.split_once(char::is_whitespace) {
+            return parse_netmask(s, address, netmask.trim_start());
+        }
+        match s.split_once('/') {
+            Some((address, netmask)) if netmask.contains('.') => parse_netmask(s, address, netmask),
+            Some(_) => Ok(LenientCidr {
+                cidrs: vec![s.parse()?],
+                normalization: Normalization::None,
+            }),
+            None => Ok(LenientCidr {
+                cidrs: vec![parse_address(s, 0)?],
+                normalization: Normalization::BareAddress,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LenientCidr, LenientParseError, Normalization};
+    use crate::cidr::cidrs;
+
+    #[test]
+    fn test_parse_each_form() {
+        // Arrange
+        let expected_results = [
+            ("10.0.0.0/24", cidrs(&["10.0.0.0/24"]), Normalization::None),
+            (
+                "10.0.0.5",
+                cidrs(&["10.0.0.5/32"]),
+                Normalization::BareAddress,
+            ),
+            (
+                "2001:db8::1",
+                cidrs(&["2001:db8::1/128"]),
+                Normalization::BareAddress,
+            ),
+            (
+                "10.0.0.0 255.255.255.0",
+                cidrs(&["10.0.0.0/24"]),
+                Normalization::Netmask,
+            ),
+            (
+                "10.0.0.0/255.255.0.0",
+                cidrs(&["10.0.0.0/16"]),
+                Normalization::Netmask,
+            ),
+            (
+                "10.0.0.1-10.0.0.77",
+                cidrs(&[
+                    "10.0.0.1/32",
+                    "10.0.0.2/31",
+                    "10.0.0.4/30",
+                    "10.0.0.8/29",
+                    "10.0.0.16/28",
+                    "10.0.0.32/27",
+                    "10.0.0.64/29",
+                    "10.0.0.72/30",
+                    "10.0.0.76/31",
+                ]),
+                Normalization::Range,
+            ),
+            (
+                "2001:db8:: - 2001:db8::ff",
+                cidrs(&["2001:db8::/120"]),
+                Normalization::Range,
+            ),
+        ];
+
+        for (input, expected_cidrs, expected_normalization) in expected_results {
+            // Act
+            let actual_result: LenientCidr = input.parse().unwrap();
+
+            // Assert
+            assert_eq!(actual_result.cidrs, expected_cidrs);
+            assert_eq!(actual_result.normalization, expected_normalization);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_problem() {
+        // Arrange
+        let expected_errors = [
+            (
+                "10.0.0.0 255.0.255.0",
+                9..20,
+                "netmask 255.0.255.0 is not contiguous",
+            ),
+            ("10.0.0.0/255.0.0", 9..16, "'255.0.0' is not a netmask"),
+            ("10.0.0.1-10.0.0.300", 16..19, "octet 4 '300' exceeds 255"),
+            ("10.0.0.9-10.0.0.1", 0..17, "range starts after it ends"),
+            (
+                "10.0.0.1-2001:db8::1",
+                0..20,
+                "range mixes IPv4 and IPv6 addresses",
+            ),
+        ];
+
+        for (input, expected_span, expected_message) in expected_errors {
+            // Act
+            let actual_error: LenientParseError = input.parse::<LenientCidr>().unwrap_err();
+
+            // Assert
+            assert_eq!(actual_error.span(), expected_span);
+            assert_eq!(actual_error.to_string(), expected_message);
+        }
+    }
+}
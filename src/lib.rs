@@ -2,14 +2,72 @@
 //!
 //! `cidit` (**CID**R **I**nspection **T**ool) is a tool for calculating and inspecting IP ranges
 
+mod aggregate;
+mod allocate;
+mod bits;
 mod cidr;
+mod classful;
+mod conflict;
+mod hosts;
 mod inspector;
 mod ip;
+mod ipset;
+mod lenient;
+mod nav;
+mod offset;
+mod range;
+mod reverse;
+mod special;
+mod split;
+mod strict;
+mod utilization;
+mod vlsm;
 
+pub use aggregate::Aggregatable;
+pub use aggregate::AggregateError;
+pub use aggregate::SummarizedCidr;
+pub use allocate::AllocateError;
+pub use allocate::FitStrategy;
+pub use bits::AddressBits;
+pub use bits::Bits;
 pub use cidr::Cidr;
+pub use cidr::CidrParseError;
 pub use cidr::ipv4::Ipv4Cidr;
+pub use cidr::ipv4::Ipv4CidrParseError;
+pub use cidr::ipv4::Ipv4Subnets;
+pub use cidr::ipv6::Ipv6CidrParseError;
+pub use classful::AddressClass;
+pub use classful::Classful;
+pub use classful::ClassfulBoundary;
+pub use conflict::Conflict;
+pub use conflict::Conflicting;
+pub use conflict::Relation;
+pub use hosts::Hosts;
+pub use hosts::Ipv4Hosts;
+pub use hosts::RandomHosts;
+pub use inspector::InspectOptions;
 pub use inspector::Inspectable;
 pub use inspector::InspectionResult;
 pub use inspector::ipv4::Ipv4InspectionResult;
 pub use inspector::ipv6::Ipv6InspectionResult;
 pub use ipnet::Ipv6Net;
+pub use ipset::IpSet;
+pub use lenient::LenientCidr;
+pub use lenient::LenientParseError;
+pub use lenient::Normalization;
+pub use nav::NavError;
+pub use nav::Navigable;
+pub use offset::OffsetError;
+pub use offset::Offsettable;
+pub use reverse::ReverseZone;
+pub use special::SpecialPurpose;
+pub use split::SplitBy;
+pub use split::SplitError;
+pub use split::Splittable;
+pub use strict::StrictParseError;
+pub use utilization::Utilization;
+pub use vlsm::VlsmAllocation;
+pub use vlsm::VlsmError;
+pub use vlsm::VlsmPlan;
+pub use vlsm::VlsmRequest;
+pub use vlsm::VlsmRequestParseError;
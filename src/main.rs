@@ -1,65 +1,1013 @@
+mod export;
+mod firewall;
 mod print;
+mod report;
+mod zone_file;
 
-use cidit::{Cidr, Inspectable, InspectionResult};
-use clap::{Parser, ValueEnum};
+use cidit::{
+    Aggregatable, Cidr, Conflicting, FitStrategy, InspectOptions, Inspectable, InspectionResult,
+    IpSet, LenientCidr, LenientParseError, Navigable, Normalization, OffsetError, Offsettable,
+    SplitBy, Splittable, VlsmPlan, VlsmRequest,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use export::{ExportOptions, Family, Statement, Vendor};
+use firewall::Firewall;
+use print::{
+    ConflictEntry, ContainsEntry, Diagnostic, ErrorEntry, HostEntry, InspectEntry, InspectTableRow,
+    NavEntry, NavTableRow, NthEntry, PlanEntry, PlanTableRow, ReverseZoneEntry,
+    ReverseZoneTableRow, SummaryEntry, SummaryTableRow, TableRow, ToCidr,
+};
+use report::{UTILIZATION_OUTPUT_VERSION, UtilizationReport, UtilizationTableRow};
+use serde::Serialize;
+use std::cell::Cell;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::io::{BufRead, BufReader};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use tabled::Tabled;
 
 #[derive(ValueEnum, Clone, Debug)]
 enum OutputFormat {
     Json,
     Table,
     Ndjson,
+    /// nftables table with an interval set per family
+    Nftables,
+    /// ipset restore input with a hash:net set per family
+    Ipset,
+    /// iptables-restore chain for IPv4 or ip6tables-restore chain for IPv6, picked with --family
+    IptablesRestore,
+    /// pf.conf table per family
+    PfTable,
+}
+
+impl OutputFormat {
+    fn firewall(&self) -> Option<Firewall> {
+        match self {
+            OutputFormat::Json | OutputFormat::Table | OutputFormat::Ndjson => None,
+            OutputFormat::Nftables => Some(Firewall::Nftables),
+            OutputFormat::Ipset => Some(Firewall::Ipset),
+            OutputFormat::IptablesRestore => Some(Firewall::IptablesRestore),
+            OutputFormat::PfTable => Some(Firewall::PfTable),
+        }
+    }
+}
+
+/// Which free space `allocate` takes subnets from
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Strategy {
+    /// Lowest free addresses first
+    FirstFit,
+    /// Smallest free gap first, so larger gaps stay open
+    BestFit,
+    /// Smallest free aligned block first, so the largest aligned blocks stay whole
+    KeepLarge,
+}
+
+impl From<Strategy> for FitStrategy {
+    fn from(value: Strategy) -> Self {
+        match value {
+            Strategy::FirstFit => FitStrategy::FirstFit,
+            Strategy::BestFit => FitStrategy::BestFit,
+            Strategy::KeepLarge => FitStrategy::KeepLarge,
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
-    /// One or more CIDRs e.g. 10.122.33.129/24
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// One or more CIDRs e.g. 10.122.33.129/24, or - to read them from stdin, one per line
     cidrs: Vec<String>,
 
-    #[arg(short, long, default_value = "table")]
+    /// Also read CIDRs from a file, one per line. Blank lines and # comments are skipped
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    #[arg(short, long, default_value = "table", global = true)]
     format: OutputFormat,
 
+    /// Only write networks of this family (only for firewall formats).
+    /// iptables-restore needs it when there are networks of both families
+    #[arg(long, global = true)]
+    family: Option<Family>,
+
     /// Prettify the JSON output (only for --format=json)
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pretty: bool,
 
     /// Print table without header (only for --format=table)
-    #[arg(short = 'H', long)]
+    #[arg(short = 'H', long, global = true)]
     headless: bool,
+
+    /// Also accept bare IPs, dotted netmasks (10.0.0.0 255.255.255.0 or 10.0.0.0/255.255.255.0)
+    /// and address ranges (10.0.0.1-10.0.0.77). Notes how each of them was read on stderr
+    #[arg(long, global = true)]
+    lenient: bool,
+
+    /// Only accept canonical CIDRs: no host bits set, no leading zeros and RFC 5952 IPv6 text.
+    /// Rejections suggest the canonical form
+    #[arg(long, global = true, conflicts_with = "lenient")]
+    strict: bool,
+
+    /// Report invalid CIDRs as error records and carry on, instead of stopping at the first one.
+    /// Exits with 1 if any of them failed
+    #[arg(long)]
+    keep_going: bool,
+
+    /// Also report the historic classful network (class A-E) of IPv4 networks, its default mask
+    /// and whether the network is a subnet or supernet of it
+    #[arg(long, global = true)]
+    classful: bool,
+
+    /// Also render the address, masks and bounds in binary (with the network/host boundary marked),
+    /// hex and integer form. Network and host bits are coloured on a terminal unless NO_COLOR is set
+    #[arg(long, global = true)]
+    bits: bool,
+
+    /// Treat the IPv6 Subnet-Router anycast address (RFC 4291) as reserved
+    #[arg(long, global = true)]
+    reserve_anycast: bool,
 }
 
-fn main() {
-    let args = Args::parse();
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Split a CIDR into equal subnets
+    Split {
+        /// CIDR to split e.g. 10.0.0.0/16
+        cidr: String,
 
-    let cidrs: Vec<Cidr> = args
-        .cidrs
-        .iter()
-        .map(|cidr| match cidr.parse::<Cidr>() {
-            Ok(cidr) => cidr,
-            Err(err) => {
-                eprintln!("Invalid CIDR '{}': {:?}", cidr, err);
-                std::process::exit(1);
+        #[command(flatten)]
+        target: SplitTarget,
+    },
+    /// Plan variable-length subnets (VLSM) inside a CIDR, largest first
+    Vlsm {
+        /// CIDR to allocate from e.g. 10.0.0.0/22
+        cidr: String,
+
+        /// Subnets to allocate as name=hosts e.g. web=500 db=60 mgmt=10
+        #[arg(required = true)]
+        subnets: Vec<VlsmRequest>,
+    },
+    /// Find the next free, aligned subnets of a pool that avoid the used CIDRs
+    Allocate {
+        /// CIDR to allocate from e.g. 10.0.0.0/16
+        #[arg(long)]
+        pool: String,
+
+        /// CIDRs already in use e.g. 10.0.0.0/24 10.0.2.0/23
+        cidrs: Vec<String>,
+
+        /// Also read used CIDRs from a file, one per line. Blank lines and # comments are skipped
+        #[arg(short, long)]
+        used: Option<PathBuf>,
+
+        /// Prefix length of the subnets e.g. 24
+        #[arg(short = 'l', long)]
+        prefix: u8,
+
+        /// Number of subnets
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+
+        /// Which free space to take the subnets from
+        #[arg(long, value_enum, default_value_t = Strategy::FirstFit)]
+        fit: Strategy,
+    },
+    /// Merge adjacent and overlapping CIDRs into the minimal set of networks
+    Aggregate {
+        /// CIDRs to merge (IPv4 and IPv6 can be mixed)
+        #[arg(required = true)]
+        cidrs: Vec<String>,
+
+        /// Summarize into at most this many networks, covering extra addresses if needed
+        #[arg(short = 'm', long)]
+        max_prefixes: Option<usize>,
+    },
+    /// Print the minimal CIDRs covering every address of the given CIDRs
+    Union {
+        #[arg(required = true)]
+        cidrs: Vec<String>,
+    },
+    /// Print the minimal CIDRs covering addresses that are in both lists
+    Intersect {
+        #[arg(required = true)]
+        cidrs: Vec<String>,
+
+        /// CIDRs to intersect with
+        #[arg(short, long, required = true, num_args = 1..)]
+        with: Vec<String>,
+    },
+    /// Print the minimal CIDRs covering the given CIDRs without the excluded ones
+    Exclude {
+        #[arg(required = true)]
+        cidrs: Vec<String>,
+
+        /// CIDRs to take out e.g. already allocated ranges
+        #[arg(short, long, required = true, num_args = 1..)]
+        minus: Vec<String>,
+    },
+    /// Print the minimal CIDRs covering every address that is not in the given CIDRs
+    Complement {
+        #[arg(required = true)]
+        cidrs: Vec<String>,
+
+        /// Only complement inside this CIDR, instead of the whole address space of each family
+        #[arg(short, long)]
+        within: Option<String>,
+    },
+    /// Check whether IPs or CIDRs fall inside a network. Exits with 0 if all of them do,
+    /// 1 if any of them does not and 2 if an input cannot be parsed
+    Contains {
+        /// Network to look in e.g. 10.0.0.0/8
+        network: String,
+
+        /// IPs or CIDRs to look up e.g. 10.1.2.3 10.2.0.0/16
+        #[arg(required = true)]
+        candidates: Vec<String>,
+
+        /// Print nothing, only set the exit code
+        #[arg(short, long)]
+        quiet: bool,
+    },
+    /// Report every pair of overlapping CIDRs. Exits with 1 if there is any
+    Conflicts {
+        /// CIDRs to check e.g. 10.0.0.0/16 10.0.5.0/24
+        #[arg(required_unless_present = "input")]
+        cidrs: Vec<String>,
+
+        /// Also read CIDRs from a file, one per line. Blank lines and # comments are skipped
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Print the reverse DNS zones (in-addr.arpa, ip6.arpa) covering CIDRs. IPv4 networks longer
+    /// than /24 get RFC 2317 classless delegation names
+    Reverse {
+        /// CIDRs to look up e.g. 192.0.2.0/26 2001:db8::/48
+        #[arg(required_unless_present = "input")]
+        cidrs: Vec<String>,
+
+        /// Also read CIDRs from a file, one per line. Blank lines and # comments are skipped
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Print a skeleton BIND zone file per zone, with PTR placeholders, instead of the zone names
+        #[arg(long)]
+        zone_file: bool,
+    },
+    /// List the usable addresses of a CIDR, lazily so that huge networks print right away
+    Hosts {
+        /// CIDR to list e.g. 10.0.0.0/28
+        cidr: String,
+
+        /// Leave out this many addresses first
+        #[arg(long, default_value_t = 0)]
+        skip: usize,
+
+        /// Print at most this many addresses. Required with firewall formats
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Print every nth address
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "random")]
+        step: u64,
+
+        /// Print the addresses in random order, each at most once
+        #[arg(long)]
+        random: bool,
+
+        /// Seed of --random, to get the same order again
+        #[arg(long, requires = "random")]
+        seed: Option<u64>,
+    },
+    /// Print the nth usable host of a CIDR, or the address at an offset from its network address.
+    /// Given a bare address, print it plus or minus each position. Exits with 1 if any position falls outside
+    Nth {
+        /// CIDR to count in e.g. 10.0.0.0/24, or an address to add the positions to e.g. 10.0.0.255
+        target: String,
+
+        /// Host numbers counting from 1, negative ones back from the last usable host e.g. 5 -1
+        #[arg(allow_negative_numbers = true, required_unless_present = "last")]
+        positions: Vec<i128>,
+
+        /// Count from the network address instead: 0 is the network address and -1 the last address.
+        /// Only for CIDR targets
+        #[arg(long, conflicts_with = "last")]
+        offset: bool,
+
+        /// Print the last N usable hosts instead of positions
+        #[arg(long, conflicts_with = "positions")]
+        last: Option<usize>,
+    },
+    /// Print the parent, sibling (buddy) block and the previous and next networks of the same size
+    /// around CIDRs. Exits with 1 if --parent is longer than a CIDR's prefix
+    Nav {
+        /// CIDRs to start from e.g. 10.1.4.0/23
+        #[arg(required = true)]
+        cidrs: Vec<String>,
+
+        /// Prefix length of the parent e.g. 16. Defaults to one bit shorter than each CIDR
+        #[arg(long)]
+        parent: Option<u8>,
+    },
+    /// Report how much of a pool its allocations use and how fragmented the free space is.
+    /// Warns about allocations outside the pool
+    Utilization {
+        /// Pool the CIDRs are allocated from e.g. 10.0.0.0/16
+        #[arg(long)]
+        pool: String,
+
+        /// Allocated CIDRs e.g. 10.0.0.0/24 10.0.2.0/23
+        cidrs: Vec<String>,
+
+        /// Also read allocated CIDRs from a file, one per line. Blank lines and # comments are skipped
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Print a text histogram of allocations and free blocks by prefix length instead
+        #[arg(long)]
+        histogram: bool,
+    },
+    /// Write ready-to-paste device config (ACLs, prefix lists or static routes) for CIDRs.
+    /// Prints plain text, --format does not apply
+    Export {
+        /// CIDRs to export e.g. 10.0.0.0/24 2001:db8::/32
+        #[arg(required_unless_present = "input")]
+        cidrs: Vec<String>,
+
+        /// Also read CIDRs from a file, one per line. Blank lines and # comments are skipped
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Device dialect to write
+        #[arg(long)]
+        vendor: Vendor,
+
+        /// Kind of statement to write
+        #[arg(short, long)]
+        statement: Statement,
+
+        /// Name of the ACL, filter or prefix list
+        #[arg(short, long, default_value = "CIDIT")]
+        name: String,
+
+        /// Deny the networks instead of permitting them
+        #[arg(long)]
+        deny: bool,
+
+        /// Next hop of static routes e.g. 192.0.2.1
+        #[arg(long, required_if_eq("statement", "route"))]
+        next_hop: Option<IpAddr>,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+#[group(required = true, multiple = false)]
+struct SplitTarget {
+    /// Prefix length of the subnets e.g. 24
+    #[arg(short = 'l', long)]
+    prefix: Option<u8>,
+
+    /// Number of equal subnets (a power of two) e.g. 16
+    #[arg(short = 'n', long)]
+    count: Option<u128>,
+}
+
+impl From<&SplitTarget> for SplitBy {
+    fn from(value: &SplitTarget) -> Self {
+        match (value.prefix, value.count) {
+            (Some(prefix), _) => SplitBy::Prefix(prefix),
+            (None, Some(count)) => SplitBy::Count(count),
+            (None, None) => unreachable!("clap requires either --prefix or --count"),
+        }
+    }
+}
+
+fn boxed<E: Diagnostic + 'static>(err: E) -> Box<dyn Diagnostic> {
+    Box::new(err)
+}
+
+/// Parses CIDRs, also the forms accepted by [`LenientCidr`] with --lenient,
+/// or only canonical ones with --strict
+#[derive(Clone, Copy)]
+struct CidrParser {
+    lenient: bool,
+    strict: bool,
+    /// Exit code when an input cannot be parsed
+    error_code: i32,
+}
+
+impl CidrParser {
+    /// CIDRs of one input. Notes on stderr how a lenient input was read
+    fn try_parse(&self, input: &str) -> Result<Vec<Cidr>, Box<dyn Diagnostic>> {
+        if self.strict {
+            return Ok(vec![Cidr::parse_strict(input).map_err(boxed)?]);
+        }
+        if !self.lenient {
+            return Ok(vec![
+                input
+                    .parse::<Cidr>()
+                    .map_err(|err| boxed(LenientParseError::from(err)))?,
+            ]);
+        }
+        let lenient: LenientCidr = input.parse().map_err(boxed)?;
+        match lenient.cidrs.as_slice() {
+            _ if lenient.normalization == Normalization::None => {}
+            [cidr] => eprintln!(
+                "note: read '{}' as {} ({})",
+                input, cidr, lenient.normalization
+            ),
+            cidrs => eprintln!(
+                "note: read '{}' as {} CIDRs ({})",
+                input,
+                cidrs.len(),
+                lenient.normalization
+            ),
+        }
+        Ok(lenient.cidrs)
+    }
+
+    fn parse(&self, input: &str) -> Vec<Cidr> {
+        self.try_parse(input).unwrap_or_else(|err| {
+            eprintln!("{}", print::render_parse_error(input, err.as_ref()));
+            std::process::exit(self.error_code);
+        })
+    }
+
+    /// Parses an input that must be a single network
+    fn parse_one(&self, input: &str) -> Cidr {
+        match self.parse(input).as_slice() {
+            [cidr] => *cidr,
+            cidrs => {
+                eprintln!(
+                    "error: '{}' is {} CIDRs, expected a single one",
+                    input,
+                    cidrs.len()
+                );
+                std::process::exit(self.error_code);
             }
+        }
+    }
+
+    /// Parses an input, always accepting a bare IP as a single host network
+    fn parse_or_ip(&self, input: &str) -> Vec<Cidr> {
+        match input.parse::<IpAddr>() {
+            Ok(ip) => vec![Cidr::from(ip)],
+            Err(_) => self.parse(input),
+        }
+    }
+
+    fn parse_all(&self, cidrs: &[String]) -> Vec<Cidr> {
+        read_cidrs(cidrs, None)
+            .flat_map(|cidr| self.parse(&cidr))
+            .collect()
+    }
+
+    fn parse_set(&self, cidrs: &[String]) -> IpSet {
+        self.parse_all(cidrs).into_iter().collect()
+    }
+}
+
+/// Lines of `reader` with blank lines and # comments skipped
+fn cidr_lines(reader: impl BufRead, source: String) -> impl Iterator<Item = String> {
+    reader
+        .lines()
+        .map(move |line| {
+            line.unwrap_or_else(|err| {
+                eprintln!("Cannot read '{}': {}", source, err);
+                std::process::exit(1);
+            })
+        })
+        .filter_map(|line| {
+            let cidr = line.split('#').next().unwrap_or_default().trim();
+            (!cidr.is_empty()).then(|| cidr.to_string())
         })
-        .collect::<Vec<Cidr>>();
+}
 
-    let inspection_results: Vec<InspectionResult> = cidrs
-        .iter()
+/// CIDRs from the arguments, where `-` reads stdin, followed by the ones in `input`.
+/// Lines are read lazily, so results can be printed while the input is still coming in
+fn read_cidrs(cidrs: &[String], input: Option<&Path>) -> impl Iterator<Item = String> + use<> {
+    let cidrs: Vec<String> = cidrs.to_vec();
+    let from_args = cidrs
+        .into_iter()
+        .flat_map(|cidr| -> Box<dyn Iterator<Item = String>> {
+            match cidr.as_str() {
+                "-" => Box::new(cidr_lines(std::io::stdin().lock(), "stdin".to_string())),
+                _ => Box::new(std::iter::once(cidr)),
+            }
+        });
+    let from_input = input.map(|path| {
+        let file = File::open(path).unwrap_or_else(|err| {
+            eprintln!("Cannot read '{}': {}", path.display(), err);
+            std::process::exit(1);
+        });
+        cidr_lines(BufReader::new(file), path.display().to_string())
+    });
+    from_args.chain(from_input.into_iter().flatten())
+}
+
+/// Whole address space of every family present in `set`
+fn address_space(set: &IpSet) -> IpSet {
+    set.cidrs()
+        .into_iter()
         .map(|cidr| match cidr {
-            Cidr::V4(v4) => v4.inspect(),
-            Cidr::V6(v6) => v6.inspect(),
+            Cidr::V4(_) => "0.0.0.0/0".parse::<Cidr>().expect("valid CIDR"),
+            Cidr::V6(_) => "::/0".parse::<Cidr>().expect("valid CIDR"),
         })
-        .collect();
+        .collect()
+}
+
+fn inspect(cidr: &Cidr, inspect_options: &InspectOptions) -> InspectionResult {
+    match cidr {
+        Cidr::V4(v4) => v4.inspect_with(inspect_options),
+        Cidr::V6(v6) => v6.inspect_with(inspect_options),
+    }
+}
+
+fn split(
+    cidr: Cidr,
+    by: SplitBy,
+    inspect_options: InspectOptions,
+) -> Box<dyn Iterator<Item = InspectionResult>> {
+    let subnets: Result<Box<dyn Iterator<Item = InspectionResult>>, _> = match cidr {
+        Cidr::V4(v4) => v4.split(by).map(|subnets| {
+            Box::new(subnets.map(move |subnet| subnet.inspect_with(&inspect_options))) as Box<_>
+        }),
+        Cidr::V6(v6) => v6.split(by).map(|subnets| {
+            Box::new(subnets.map(move |subnet| subnet.inspect_with(&inspect_options))) as Box<_>
+        }),
+    };
+    subnets.unwrap_or_else(|err| {
+        eprintln!("Cannot split '{}': {}", cidr, err);
+        std::process::exit(1);
+    })
+}
+
+fn vlsm(
+    cidr: Cidr,
+    requests: &[VlsmRequest],
+    inspect_options: InspectOptions,
+) -> impl Iterator<Item = PlanEntry> {
+    let plan = VlsmPlan::allocate(&cidr, requests).unwrap_or_else(|err| {
+        eprintln!("Cannot plan '{}': {}", cidr, err);
+        std::process::exit(1);
+    });
+    let allocated = plan
+        .allocations
+        .into_iter()
+        .map(move |allocation| PlanEntry {
+            status: "allocated",
+            name: Some(allocation.name),
+            requested_hosts: Some(allocation.hosts),
+            inspection_result: inspect(&allocation.cidr, &inspect_options),
+        });
+    let free = plan.free.into_iter().map(move |cidr| PlanEntry {
+        status: "free",
+        name: None,
+        requested_hosts: None,
+        inspection_result: inspect(&cidr, &inspect_options),
+    });
+    allocated.chain(free)
+}
+
+fn summarize(
+    cidrs: &[Cidr],
+    max_prefixes: usize,
+    inspect_options: InspectOptions,
+) -> impl Iterator<Item = SummaryEntry> {
+    let summary = cidrs.summarize(max_prefixes).unwrap_or_else(|err| {
+        eprintln!("Cannot summarize: {}", err);
+        std::process::exit(1);
+    });
+    summary.into_iter().map(move |summarized| SummaryEntry {
+        extra_addresses: summarized.extra_addresses,
+        inspection_result: inspect(&summarized.cidr, &inspect_options),
+    })
+}
+
+fn inspect_all(
+    cidrs: impl IntoIterator<Item = Cidr>,
+    inspect_options: InspectOptions,
+) -> impl Iterator<Item = InspectionResult> {
+    cidrs
+        .into_iter()
+        .map(move |cidr| inspect(&cidr, &inspect_options))
+}
 
+fn print<T, R>(items: impl Iterator<Item = T>, args: &Args) -> std::io::Result<()>
+where
+    T: Serialize + ToCidr,
+    R: Tabled + From<T>,
+{
+    if let Some(firewall) = args.format.firewall() {
+        let cidrs: Vec<Cidr> = items.filter_map(|item| item.to_cidr()).collect();
+        let lines = firewall::render(firewall, &cidrs, args.family).unwrap_or_else(|err| {
+            eprintln!("error: {err}");
+            std::process::exit(2);
+        });
+        return print::print_lines(lines.into_iter());
+    }
     match args.format {
-        OutputFormat::Json => {
-            print::print_json(inspection_results, &args.pretty);
-        }
+        OutputFormat::Json => print::print_json(items, &args.pretty),
         OutputFormat::Table => {
-            print::print_table(inspection_results, &args.headless);
+            let mut hidden_columns = Vec::new();
+            if !args.classful {
+                hidden_columns.extend_from_slice(print::CLASSFUL_COLUMNS);
+            }
+            if !args.bits {
+                hidden_columns.push(print::BITS_COLUMN);
+            }
+            print::print_table(items.map(R::from), &args.headless, &hidden_columns)
         }
-        OutputFormat::Ndjson => {
-            print::print_ndjson(inspection_results);
+        OutputFormat::Ndjson => print::print_ndjson(items),
+        _ => unreachable!("firewall formats are printed above"),
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let inspect_options = InspectOptions {
+        reserve_subnet_router_anycast: args.reserve_anycast,
+        classful: args.classful,
+        bits: args.bits,
+    };
+
+    let parser = CidrParser {
+        lenient: args.lenient,
+        strict: args.strict,
+        error_code: 1,
+    };
+
+    if let Some(
+        Command::Contains { .. }
+        | Command::Conflicts { .. }
+        | Command::Reverse { .. }
+        | Command::Utilization { .. },
+    ) = &args.command
+        && args.format.firewall().is_some()
+    {
+        eprintln!(
+            "error: firewall formats write networks, contains, conflicts, reverse and utilization print json, ndjson or table"
+        );
+        std::process::exit(2);
+    }
+
+    // Firewall formats collect every network before writing, which never ends for a huge network
+    if let Some(Command::Hosts { limit: None, .. }) = &args.command
+        && args.format.firewall().is_some()
+    {
+        eprintln!("error: hosts needs --limit with firewall formats");
+        std::process::exit(2);
+    }
+
+    let mut exit_code = 0;
+    let any_failed = Cell::new(false);
+    let printed = match &args.command {
+        None if args.keep_going => {
+            let entries = read_cidrs(&args.cidrs, args.input.as_deref()).flat_map(|input| {
+                match parser.try_parse(&input) {
+                    Ok(cidrs) => cidrs
+                        .iter()
+                        .map(|cidr| {
+                            InspectEntry::Inspected(Box::new(inspect(cidr, &inspect_options)))
+                        })
+                        .collect(),
+                    Err(err) => {
+                        any_failed.set(true);
+                        vec![InspectEntry::Failed(ErrorEntry::new(input, err.as_ref()))]
+                    }
+                }
+            });
+            print::<_, InspectTableRow>(entries, &args)
+        }
+        None => {
+            let cidrs =
+                read_cidrs(&args.cidrs, args.input.as_deref()).flat_map(|cidr| parser.parse(&cidr));
+            print::<_, TableRow>(inspect_all(cidrs, inspect_options), &args)
+        }
+        Some(Command::Split { cidr, target }) => print::<_, TableRow>(
+            split(parser.parse_one(cidr), target.into(), inspect_options),
+            &args,
+        ),
+        Some(Command::Vlsm { cidr, subnets }) => print::<_, PlanTableRow>(
+            vlsm(parser.parse_one(cidr), subnets, inspect_options),
+            &args,
+        ),
+        Some(Command::Allocate {
+            pool,
+            cidrs,
+            used,
+            prefix,
+            count,
+            fit,
+        }) => {
+            let used: IpSet = read_cidrs(cidrs, used.as_deref())
+                .flat_map(|cidr| parser.parse(&cidr))
+                .collect();
+            let subnets = parser
+                .parse_one(pool)
+                .allocate(&used, *prefix, *count, (*fit).into())
+                .unwrap_or_else(|err| {
+                    eprintln!("Cannot allocate: {}", err);
+                    std::process::exit(1);
+                });
+            print::<_, TableRow>(inspect_all(subnets, inspect_options), &args)
+        }
+        Some(Command::Aggregate {
+            cidrs,
+            max_prefixes,
+        }) => {
+            let cidrs = parser.parse_all(cidrs);
+            match max_prefixes {
+                None => {
+                    print::<_, TableRow>(inspect_all(cidrs.aggregate(), inspect_options), &args)
+                }
+                Some(max_prefixes) => print::<_, SummaryTableRow>(
+                    summarize(&cidrs, *max_prefixes, inspect_options),
+                    &args,
+                ),
+            }
+        }
+        Some(Command::Union { cidrs }) => print::<_, TableRow>(
+            inspect_all(parser.parse_set(cidrs).cidrs(), inspect_options),
+            &args,
+        ),
+        Some(Command::Intersect { cidrs, with }) => {
+            let intersection = parser
+                .parse_set(cidrs)
+                .intersection(&parser.parse_set(with));
+            print::<_, TableRow>(inspect_all(intersection.cidrs(), inspect_options), &args)
+        }
+        Some(Command::Exclude { cidrs, minus }) => {
+            let difference = parser.parse_set(cidrs).difference(&parser.parse_set(minus));
+            print::<_, TableRow>(inspect_all(difference.cidrs(), inspect_options), &args)
+        }
+        Some(Command::Complement { cidrs, within }) => {
+            let set = parser.parse_set(cidrs);
+            let within = match within {
+                Some(within) => IpSet::from(parser.parse_one(within)),
+                None => address_space(&set),
+            };
+            let complement = set.complement().intersection(&within);
+            print::<_, TableRow>(inspect_all(complement.cidrs(), inspect_options), &args)
         }
+        Some(Command::Contains {
+            network,
+            candidates,
+            quiet,
+        }) => {
+            // 1 is the answer "no", so unparsable input exits with 2 like a usage error
+            let parser = CidrParser {
+                error_code: 2,
+                ..parser
+            };
+            let parsed_network = parser.parse_one(network);
+            let entries: Vec<ContainsEntry> = candidates
+                .iter()
+                .map(|candidate| ContainsEntry {
+                    network: network.clone(),
+                    candidate: candidate.clone(),
+                    contained: parser
+                        .parse_or_ip(candidate)
+                        .iter()
+                        .all(|cidr| parsed_network.is_supernet_of(cidr)),
+                })
+                .collect();
+            exit_code = match entries.iter().all(|entry| entry.contained) {
+                true => 0,
+                false => 1,
+            };
+            match quiet {
+                true => Ok(()),
+                false => print::<_, ContainsEntry>(entries.into_iter(), &args),
+            }
+        }
+        Some(Command::Conflicts { cidrs, input }) => {
+            let cidrs: Vec<Cidr> = read_cidrs(cidrs, input.as_deref())
+                .flat_map(|cidr| parser.parse(&cidr))
+                .collect();
+            let conflicts = cidrs.conflicts();
+            if !conflicts.is_empty() {
+                exit_code = 1;
+            }
+            let entries = conflicts.into_iter().map(|conflict| ConflictEntry {
+                first: conflict.first.to_string(),
+                second: conflict.second.to_string(),
+                relation: conflict.relation.to_string(),
+            });
+            print::<_, ConflictEntry>(entries, &args)
+        }
+        Some(Command::Reverse {
+            cidrs,
+            input,
+            zone_file,
+        }) => {
+            let cidrs = read_cidrs(cidrs, input.as_deref()).flat_map(|cidr| parser.parse(&cidr));
+            let zones = cidrs.flat_map(|cidr| {
+                cidr.reverse_zones()
+                    .into_iter()
+                    .map(move |zone| (cidr, zone))
+            });
+            match zone_file {
+                true => print::print_lines(zones.enumerate().flat_map(|(index, (cidr, zone))| {
+                    let separator = (index > 0).then(String::new);
+                    separator.into_iter().chain(zone_file::render(&zone, &cidr))
+                })),
+                false => print::<_, ReverseZoneTableRow>(
+                    zones.map(|(cidr, zone)| ReverseZoneEntry {
+                        cidr: cidr.to_string(),
+                        zone: zone.name,
+                        network: zone.network.to_string(),
+                        parent: zone.parent,
+                    }),
+                    &args,
+                ),
+            }
+        }
+        Some(Command::Hosts {
+            cidr,
+            skip,
+            limit,
+            step,
+            random,
+            seed,
+        }) => {
+            let cidr = parser.parse_one(cidr);
+            let hosts: Box<dyn Iterator<Item = IpAddr>> = match random {
+                true => {
+                    let seed = seed.unwrap_or_else(|| RandomState::new().build_hasher().finish());
+                    Box::new(cidr.random_hosts(seed))
+                }
+                false => Box::new(cidr.hosts()),
+            };
+            let hosts = hosts
+                .skip(*skip)
+                .step_by(usize::try_from(*step).unwrap_or(usize::MAX))
+                .take(limit.unwrap_or(usize::MAX));
+            match args.format {
+                OutputFormat::Table => print::print_lines(hosts.map(|host| host.to_string())),
+                _ => print::<_, HostEntry>(hosts.map(|address| HostEntry { address }), &args),
+            }
+        }
+        Some(Command::Nth {
+            target,
+            positions,
+            offset,
+            last,
+        }) => {
+            let ip = target.parse::<IpAddr>().ok();
+            if ip.is_some() && *offset {
+                eprintln!(
+                    "error: --offset counts from the network address of a CIDR, '{}' is an address",
+                    target
+                );
+                std::process::exit(2);
+            }
+            let entries: Vec<NthEntry> = match (ip, last) {
+                (_, Some(last)) => {
+                    let cidr = parser.parse_one(target);
+                    let mut hosts: Vec<IpAddr> = cidr.hosts().rev().take(*last).collect();
+                    hosts.reverse();
+                    if hosts.len() < *last {
+                        eprintln!(
+                            "error: {}",
+                            OffsetError::NoSuchHost {
+                                host: -(*last as i128),
+                                cidr,
+                            }
+                        );
+                        exit_code = 1;
+                    }
+                    let count = hosts.len() as i128;
+                    (-count..)
+                        .zip(hosts)
+                        .map(|(position, address)| NthEntry { position, address })
+                        .collect()
+                }
+                (ip, None) => {
+                    let cidr = ip.is_none().then(|| parser.parse_one(target));
+                    positions
+                        .iter()
+                        .filter_map(|position| {
+                            let address = match (ip, cidr) {
+                                (Some(ip), _) => ip.offset(*position),
+                                (_, Some(cidr)) if *offset => cidr.address_at(*position),
+                                (_, Some(cidr)) => cidr.host(*position),
+                                (None, None) => {
+                                    unreachable!("targets are either an IP or a CIDR")
+                                }
+                            };
+                            address
+                                .inspect_err(|err| {
+                                    eprintln!("error: {}", err);
+                                    exit_code = 1;
+                                })
+                                .ok()
+                                .map(|address| NthEntry {
+                                    position: *position,
+                                    address,
+                                })
+                        })
+                        .collect()
+                }
+            };
+            print::<_, NthEntry>(entries.into_iter(), &args)
+        }
+        Some(Command::Nav { cidrs, parent }) => {
+            let mut entries = Vec::new();
+            for cidr in parser.parse_all(cidrs) {
+                let parent = match parent {
+                    Some(prefix) => cidr.parent(*prefix).map(Some).unwrap_or_else(|err| {
+                        eprintln!("error: {}: {}", cidr, err);
+                        exit_code = 1;
+                        None
+                    }),
+                    None => cidr
+                        .prefix_len()
+                        .checked_sub(1)
+                        .and_then(|prefix| cidr.parent(prefix).ok()),
+                };
+                let neighbours = [
+                    ("parent", parent),
+                    ("sibling", cidr.sibling().ok()),
+                    ("previous", cidr.previous().ok()),
+                    ("next", cidr.next().ok()),
+                ];
+                entries.extend(neighbours.into_iter().map(|(relation, network)| NavEntry {
+                    cidr: cidr.to_string(),
+                    relation,
+                    network: network.map(|network| network.to_string()),
+                }));
+            }
+            print::<_, NavTableRow>(entries.into_iter(), &args)
+        }
+        Some(Command::Utilization {
+            pool,
+            cidrs,
+            input,
+            histogram,
+        }) => {
+            let pool = parser.parse_one(pool);
+            let allocations: Vec<Cidr> = read_cidrs(cidrs, input.as_deref())
+                .flat_map(|cidr| parser.parse(&cidr))
+                .collect();
+            let utilization = pool.utilization(&allocations);
+            for allocation in &utilization.outside {
+                eprintln!("warning: {} is outside the pool {}", allocation, pool);
+            }
+            let report = UtilizationReport::from(utilization);
+            match (histogram, &args.format) {
+                (true, _) => print::print_lines(report::histogram(&report).into_iter()),
+                (false, OutputFormat::Json) => {
+                    print::print_json_report(&report, UTILIZATION_OUTPUT_VERSION, &args.pretty)
+                }
+                (false, OutputFormat::Ndjson) => print::print_ndjson(std::iter::once(&report)),
+                (false, _) => print::print_table(
+                    std::iter::once(UtilizationTableRow::from(&report)),
+                    &args.headless,
+                    &[],
+                ),
+            }
+        }
+        Some(Command::Export {
+            cidrs,
+            input,
+            vendor,
+            statement,
+            name,
+            deny,
+            next_hop,
+        }) => {
+            let cidrs: Vec<Cidr> = read_cidrs(cidrs, input.as_deref())
+                .flat_map(|cidr| parser.parse(&cidr))
+                .collect();
+            let options = ExportOptions {
+                name: name.clone(),
+                deny: *deny,
+                next_hop: *next_hop,
+            };
+            let lines =
+                export::export(&cidrs, *vendor, *statement, &options).unwrap_or_else(|err| {
+                    eprintln!("Cannot export: {}", err);
+                    std::process::exit(1);
+                });
+            print::print_lines(lines.into_iter())
+        }
+    };
+
+    if let Err(err) = printed
+        && err.kind() != std::io::ErrorKind::BrokenPipe
+    {
+        eprintln!("Failed to print results: {}", err);
+        std::process::exit(1);
+    }
+    if any_failed.get() {
+        exit_code = 1;
     }
+    std::process::exit(exit_code);
 }
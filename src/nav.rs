@@ -0,0 +1,271 @@
+//! Navigation between neighbouring networks: parents, siblings and adjacent blocks
+
+use crate::cidr::Cidr;
+use crate::cidr::ipv4::{Ipv4Cidr, Network};
+use crate::range::{IPV4_BITS, IPV6_BITS, block_last, block_size_minus_one};
+use ipnet::Ipv6Net;
+use std::net::Ipv6Addr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum NavError {
+    #[error("Invalid parent prefix: /{new} (expected /0 to /{current})")]
+    InvalidPrefix { new: u8, current: u8 },
+    #[error("A /0 has no sibling")]
+    NoSibling,
+    #[error("No /{prefix} before the start of the address space")]
+    BeforeStart { prefix: u8 },
+    #[error("No /{prefix} after the end of the address space")]
+    AfterEnd { prefix: u8 },
+}
+
+/// Network as `(first address, prefix length)`, see [`crate::range`]
+type Block = (u128, u8);
+
+fn parent((start, prefix): Block, bits: u8, new: u8) -> Result<Block, NavError> {
+    if new > prefix {
+        return Err(NavError::InvalidPrefix {
+            new,
+            current: prefix,
+        });
+    }
+    Ok((start & !block_size_minus_one(bits - new), new))
+}
+
+fn sibling((start, prefix): Block, bits: u8) -> Result<Block, NavError> {
+    match prefix {
+        0 => Err(NavError::NoSibling),
+        _ => Ok((start ^ (1 << (bits - prefix)), prefix)),
+    }
+}
+
+fn previous((start, prefix): Block, bits: u8) -> Result<Block, NavError> {
+    start
+        .checked_sub(block_size_minus_one(bits - prefix))
+        .and_then(|last| last.checked_sub(1))
+        .map(|start| (start, prefix))
+        .ok_or(NavError::BeforeStart { prefix })
+}
+
+fn next((start, prefix): Block, bits: u8) -> Result<Block, NavError> {
+    block_last(start, prefix, bits)
+        .checked_add(1)
+        .filter(|start| *start <= block_size_minus_one(bits))
+        .map(|start| (start, prefix))
+        .ok_or(NavError::AfterEnd { prefix })
+}
+
+/// Trait that provides navigation to the networks around a network
+///
+/// Host bits are ignored, every result is a network address
+///
+/// # Example
+/// ```
+/// use cidit::{Ipv4Cidr, Navigable};
+///
+/// let cidr: Ipv4Cidr = "10.1.4.0/23".parse().unwrap();
+///
+/// assert_eq!(cidr.parent(16), Ok("10.1.0.0/16".parse().unwrap()));
+/// assert_eq!(cidr.sibling(), Ok("10.1.6.0/23".parse().unwrap()));
+/// assert_eq!(cidr.previous(), Ok("10.1.2.0/23".parse().unwrap()));
+/// assert_eq!(cidr.next(), Ok("10.1.6.0/23".parse().unwrap()));
+/// ```
+pub trait Navigable: Sized {
+    /// Network with the shorter (or equal) `prefix` length containing this one
+    fn parent(&self, prefix: u8) -> Result<Self, NavError>;
+
+    /// Other half of the parent one bit shorter, also known as the buddy block
+    fn sibling(&self) -> Result<Self, NavError>;
+
+    /// Adjacent network of the same size right before this one
+    fn previous(&self) -> Result<Self, NavError>;
+
+    /// Adjacent network of the same size right after this one
+    fn next(&self) -> Result<Self, NavError>;
+}
+
+impl Ipv4Cidr {
+    fn block(&self) -> Block {
+        (self.get_network_address().into(), self.prefix_len())
+    }
+
+    fn from_nav_block((start, prefix): Block) -> Self {
+        Ipv4Cidr::from_valid_parts(start as u32, prefix)
+    }
+}
+
+impl Navigable for Ipv4Cidr {
+    fn parent(&self, prefix: u8) -> Result<Self, NavError> {
+        parent(self.block(), IPV4_BITS, prefix).map(Ipv4Cidr::from_nav_block)
+    }
+
+    fn sibling(&self) -> Result<Self, NavError> {
+        sibling(self.block(), IPV4_BITS).map(Ipv4Cidr::from_nav_block)
+    }
+
+    fn previous(&self) -> Result<Self, NavError> {
+        previous(self.block(), IPV4_BITS).map(Ipv4Cidr::from_nav_block)
+    }
+
+    fn next(&self) -> Result<Self, NavError> {
+        next(self.block(), IPV4_BITS).map(Ipv4Cidr::from_nav_block)
+    }
+}
+
+fn ipv6_block(net: &Ipv6Net) -> Block {
+    (net.network().into(), net.prefix_len())
+}
+
+fn ipv6_net((start, prefix): Block) -> Ipv6Net {
+    Ipv6Net::new_assert(Ipv6Addr::from(start), prefix)
+}
+
+impl Navigable for Ipv6Net {
+    fn parent(&self, prefix: u8) -> Result<Self, NavError> {
+        parent(ipv6_block(self), IPV6_BITS, prefix).map(ipv6_net)
+    }
+
+    fn sibling(&self) -> Result<Self, NavError> {
+        sibling(ipv6_block(self), IPV6_BITS).map(ipv6_net)
+    }
+
+    fn previous(&self) -> Result<Self, NavError> {
+        previous(ipv6_block(self), IPV6_BITS).map(ipv6_net)
+    }
+
+    fn next(&self) -> Result<Self, NavError> {
+        next(ipv6_block(self), IPV6_BITS).map(ipv6_net)
+    }
+}
+
+impl Navigable for Cidr {
+    fn parent(&self, prefix: u8) -> Result<Self, NavError> {
+        match self {
+            Cidr::V4(v4) => v4.parent(prefix).map(Cidr::V4),
+            Cidr::V6(v6) => v6.parent(prefix).map(Cidr::V6),
+        }
+    }
+
+    fn sibling(&self) -> Result<Self, NavError> {
+        match self {
+            Cidr::V4(v4) => v4.sibling().map(Cidr::V4),
+            Cidr::V6(v6) => v6.sibling().map(Cidr::V6),
+        }
+    }
+
+    fn previous(&self) -> Result<Self, NavError> {
+        match self {
+            Cidr::V4(v4) => v4.previous().map(Cidr::V4),
+            Cidr::V6(v6) => v6.previous().map(Cidr::V6),
+        }
+    }
+
+    fn next(&self) -> Result<Self, NavError> {
+        match self {
+            Cidr::V4(v4) => v4.next().map(Cidr::V4),
+            Cidr::V6(v6) => v6.next().map(Cidr::V6),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NavError, Navigable};
+    use crate::cidr::cidr;
+
+    #[test]
+    fn test_parent() {
+        // Arrange
+        let expected_parents = [
+            ("10.1.4.0/23", 22, "10.1.4.0/22"),
+            ("10.1.4.0/23", 21, "10.1.0.0/21"),
+            ("10.1.5.7/23", 23, "10.1.4.0/23"),
+            ("10.1.4.0/23", 0, "0.0.0.0/0"),
+            ("2001:db8:1::/48", 32, "2001:db8::/32"),
+        ];
+
+        for (network, prefix, expected_parent) in expected_parents {
+            // Act
+            let actual_parent = cidr(network).parent(prefix);
+
+            // Assert
+            assert_eq!(actual_parent, Ok(cidr(expected_parent)), "{network}");
+        }
+    }
+
+    #[test]
+    fn test_parent_longer_prefix() {
+        // Arrange
+        let expected_cidr = cidr("10.1.4.0/23");
+
+        // Act
+        let actual_parent = expected_cidr.parent(24);
+
+        // Assert
+        assert_eq!(
+            actual_parent,
+            Err(NavError::InvalidPrefix {
+                new: 24,
+                current: 23
+            })
+        );
+    }
+
+    #[test]
+    fn test_sibling() {
+        // Arrange
+        let expected_siblings = [
+            ("10.1.4.0/23", Ok("10.1.6.0/23")),
+            ("10.1.6.0/23", Ok("10.1.4.0/23")),
+            ("128.0.0.0/1", Ok("0.0.0.0/1")),
+            ("2001:db8::/32", Ok("2001:db9::/32")),
+            ("0.0.0.0/0", Err(NavError::NoSibling)),
+        ];
+
+        for (network, expected_sibling) in expected_siblings {
+            // Act
+            let actual_sibling = cidr(network).sibling();
+
+            // Assert
+            assert_eq!(actual_sibling, expected_sibling.map(cidr), "{network}");
+        }
+    }
+
+    #[test]
+    fn test_previous_and_next() {
+        // Arrange
+        let expected_neighbours = [
+            ("10.1.4.0/23", Ok("10.1.2.0/23"), Ok("10.1.6.0/23")),
+            (
+                "0.0.0.0/24",
+                Err(NavError::BeforeStart { prefix: 24 }),
+                Ok("0.0.1.0/24"),
+            ),
+            (
+                "255.255.255.255/32",
+                Ok("255.255.255.254/32"),
+                Err(NavError::AfterEnd { prefix: 32 }),
+            ),
+            (
+                "::/0",
+                Err(NavError::BeforeStart { prefix: 0 }),
+                Err(NavError::AfterEnd { prefix: 0 }),
+            ),
+            (
+                "ffff::/16",
+                Ok("fffe::/16"),
+                Err(NavError::AfterEnd { prefix: 16 }),
+            ),
+        ];
+
+        for (network, expected_previous, expected_next) in expected_neighbours {
+            // Act
+            let actual_previous = cidr(network).previous();
+            let actual_next = cidr(network).next();
+
+            // Assert
+            assert_eq!(actual_previous, expected_previous.map(cidr), "{network}");
+            assert_eq!(actual_next, expected_next.map(cidr), "{network}");
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Checked address arithmetic: nth host, offsets inside a network and adding integers to addresses
+
+use crate::cidr::Cidr;
+use crate::cidr::ipv4::{Ipv4Cidr, Network};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum OffsetError {
+    #[error("Offset {offset} is outside {cidr}")]
+    OutOfRange { offset: i128, cidr: Cidr },
+    #[error("{cidr} has no host {host} (hosts are counted from 1, or back from -1)")]
+    NoSuchHost { host: i128, cidr: Cidr },
+    #[error("{addr} {delta:+} is outside the address space")]
+    Overflow { addr: IpAddr, delta: i128 },
+}
+
+/// Address `offset` steps into `first..=last`. Negative offsets count back from `last`, -1 being `last` itself
+fn offset_in(first: u128, last: u128, offset: i128) -> Option<u128> {
+    match offset >= 0 {
+        true => first
+            .checked_add(offset.unsigned_abs())
+            .filter(|addr| *addr <= last),
+        false => (last - first)
+            .checked_sub(offset.unsigned_abs() - 1)
+            .map(|index| first + index),
+    }
+}
+
+/// `value + delta`, if it stays within `0..=max`
+fn shift(value: u128, delta: i128, max: u128) -> Option<u128> {
+    match delta >= 0 {
+        true => value.checked_add(delta.unsigned_abs()),
+        false => value.checked_sub(delta.unsigned_abs()),
+    }
+    .filter(|addr| *addr <= max)
+}
+
+/// Position of host number `host` in its usable range, as an offset. Host 0 does not exist
+fn host_offset(host: i128) -> Option<i128> {
+    match host {
+        0 => None,
+        1.. => Some(host - 1),
+        _ => Some(host),
+    }
+}
+
+/// Trait that provides checked `offset` function on addresses
+///
+/// # Example
+/// ```
+/// use cidit::Offsettable;
+/// use std::net::Ipv4Addr;
+///
+/// let address = Ipv4Addr::new(10, 0, 0, 255);
+///
+/// assert_eq!(address.offset(1), Ok(Ipv4Addr::new(10, 0, 1, 0)));
+/// assert!(Ipv4Addr::BROADCAST.offset(1).is_err());
+/// ```
+pub trait Offsettable: Sized {
+    /// Address `delta` addresses away, or [`OffsetError::Overflow`] past either end of the address space
+    fn offset(&self, delta: i128) -> Result<Self, OffsetError>;
+}
+
+impl Offsettable for Ipv4Addr {
+    fn offset(&self, delta: i128) -> Result<Self, OffsetError> {
+        shift(u32::from(*self).into(), delta, u32::MAX.into())
+            .map(|addr| Ipv4Addr::from(addr as u32))
+            .ok_or(OffsetError::Overflow {
+                addr: IpAddr::V4(*self),
+                delta,
+            })
+    }
+}
+
+impl Offsettable for Ipv6Addr {
+    fn offset(&self, delta: i128) -> Result<Self, OffsetError> {
+        shift(u128::from(*self), delta, u128::MAX)
+            .map(Ipv6Addr::from)
+            .ok_or(OffsetError::Overflow {
+                addr: IpAddr::V6(*self),
+                delta,
+            })
+    }
+}
+
+impl Offsettable for IpAddr {
+    fn offset(&self, delta: i128) -> Result<Self, OffsetError> {
+        match self {
+            IpAddr::V4(v4) => v4.offset(delta).map(IpAddr::V4),
+            IpAddr::V6(v6) => v6.offset(delta).map(IpAddr::V6),
+        }
+    }
+}
+
+impl Ipv4Cidr {
+    /// Address `offset` steps from the network address. Negative offsets count back from the last address,
+    /// so -1 is the broadcast address and -2 the usual gateway
+    ///
+    /// # Example
+    /// ```
+    /// use cidit::Ipv4Cidr;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let cidr: Ipv4Cidr = "10.0.0.0/24".parse().unwrap();
+    ///
+    /// assert_eq!(cidr.address_at(-2), Ok(Ipv4Addr::new(10, 0, 0, 254)));
+    /// assert!(cidr.address_at(256).is_err());
+    /// ```
+    pub fn address_at(&self, offset: i128) -> Result<Ipv4Addr, OffsetError> {
+        let network_address = self.get_network_address();
+        let last_address = network_address | u32::from(self.hostmask());
+        offset_in(network_address.into(), last_address.into(), offset)
+            .map(|addr| Ipv4Addr::from(addr as u32))
+            .ok_or(OffsetError::OutOfRange {
+                offset,
+                cidr: Cidr::V4(*self),
+            })
+    }
+
+    /// Usable host number `host`, counting from 1. Negative numbers count back from the last usable host
+    ///
+    /// # Example
+    /// ```
+    /// use cidit::Ipv4Cidr;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let cidr: Ipv4Cidr = "10.0.0.0/24".parse().unwrap();
+    ///
+    /// assert_eq!(cidr.host(5), Ok(Ipv4Addr::new(10, 0, 0, 5)));
+    /// assert_eq!(cidr.host(-1), Ok(Ipv4Addr::new(10, 0, 0, 254)));
+    /// ```
+    pub fn host(&self, host: i128) -> Result<Ipv4Addr, OffsetError> {
+        // The network and broadcast addresses are not hosts, unless there is no broadcast address (/31 and /32)
+        let reserved = i128::from(self.get_broadcast_address().is_some());
+        let network_address = self.get_network_address();
+        let (network_address, last_address) = (
+            network_address.into(),
+            (network_address | u32::from(self.hostmask())).into(),
+        );
+        offset_in(network_address, last_address, reserved)
+            .zip(offset_in(network_address, last_address, -1 - reserved))
+            .zip(host_offset(host))
+            .and_then(|((first, last), offset)| offset_in(first, last, offset))
+            .map(|addr| Ipv4Addr::from(addr as u32))
+            .ok_or(OffsetError::NoSuchHost {
+                host,
+                cidr: Cidr::V4(*self),
+            })
+    }
+}
+
+impl Cidr {
+    /// Address `offset` steps from the network address. Negative offsets count back from the last address
+    pub fn address_at(&self, offset: i128) -> Result<IpAddr, OffsetError> {
+        match self {
+            Cidr::V4(v4) => v4.address_at(offset).map(IpAddr::V4),
+            Cidr::V6(_) => {
+                let (first, last) = self.range();
+                offset_in(first, last, offset)
+                    .map(|addr| IpAddr::V6(Ipv6Addr::from(addr)))
+                    .ok_or(OffsetError::OutOfRange {
+                        offset,
+                        cidr: *self,
+                    })
+            }
+        }
+    }
+
+    /// Usable host number `host`, counting from 1. Negative numbers count back from the last usable host.
+    /// Every address of an IPv6 network is usable
+    pub fn host(&self, host: i128) -> Result<IpAddr, OffsetError> {
+        match self {
+            Cidr::V4(v4) => v4.host(host).map(IpAddr::V4),
+            Cidr::V6(_) => host_offset(host)
+                .and_then(|offset| self.address_at(offset).ok())
+                .ok_or(OffsetError::NoSuchHost { host, cidr: *self }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OffsetError, Offsettable};
+    use crate::cidr::{cidr, ip};
+    use std::net::Ipv6Addr;
+
+    #[test]
+    fn test_address_at() {
+        // Arrange
+        let expected_addresses = [
+            ("10.0.0.0/24", 0, Some("10.0.0.0")),
+            ("10.0.0.0/24", 5, Some("10.0.0.5")),
+            ("10.0.0.0/24", 255, Some("10.0.0.255")),
+            ("10.0.0.0/24", -1, Some("10.0.0.255")),
+            ("10.0.0.0/24", -2, Some("10.0.0.254")),
+            ("10.0.0.0/24", -256, Some("10.0.0.0")),
+            ("10.0.0.0/24", 256, None),
+            ("10.0.0.0/24", -257, None),
+            ("0.0.0.0/0", -1, Some("255.255.255.255")),
+            ("2001:db8::/64", -1, Some("2001:db8::ffff:ffff:ffff:ffff")),
+            ("::/0", i128::MIN, Some("8000::")),
+        ];
+
+        for (network, offset, expected_address) in expected_addresses {
+            // Act
+            let actual_address = cidr(network).address_at(offset);
+
+            // Assert
+            assert_eq!(
+                actual_address.ok(),
+                expected_address.map(ip),
+                "{network} {offset}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_host() {
+        // Arrange
+        let expected_hosts = [
+            ("10.0.0.0/24", 1, Some("10.0.0.1")),
+            ("10.0.0.0/24", 5, Some("10.0.0.5")),
+            ("10.0.0.0/24", -1, Some("10.0.0.254")),
+            ("10.0.0.0/24", 254, Some("10.0.0.254")),
+            ("10.0.0.0/24", 255, None),
+            ("10.0.0.0/24", 0, None),
+            ("10.0.0.0/31", 2, Some("10.0.0.1")),
+            ("2001:db8::/127", 1, Some("2001:db8::")),
+        ];
+
+        for (network, host, expected_host) in expected_hosts {
+            // Act
+            let actual_host = cidr(network).host(host);
+
+            // Assert
+            assert_eq!(actual_host.ok(), expected_host.map(ip), "{network} {host}");
+        }
+    }
+
+    #[test]
+    fn test_host_error() {
+        // Arrange
+        let expected_cidr = cidr("10.0.0.0/30");
+
+        // Act
+        let actual_result = expected_cidr.host(3);
+
+        // Assert
+        assert_eq!(
+            actual_result,
+            Err(OffsetError::NoSuchHost {
+                host: 3,
+                cidr: expected_cidr
+            })
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        // Arrange
+        let expected_offsets = [
+            ("10.0.0.255", 1, Some("10.0.1.0")),
+            ("10.0.1.0", -1, Some("10.0.0.255")),
+            ("0.0.0.0", -1, None),
+            ("255.255.255.255", 1, None),
+            ("2001:db8::", 0x1_0000, Some("2001:db8::1:0")),
+            ("::", -1, None),
+        ];
+
+        for (addr, delta, expected_addr) in expected_offsets {
+            // Act
+            let actual_addr = ip(addr).offset(delta);
+
+            // Assert
+            assert_eq!(actual_addr.ok(), expected_addr.map(ip), "{addr} {delta}");
+        }
+        assert!(Ipv6Addr::UNSPECIFIED.offset(i128::MAX).is_ok());
+    }
+}
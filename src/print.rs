@@ -1,18 +1,38 @@
-use cidit::InspectionResult;
-use serde::Serialize;
+use cidit::{Bits, Cidr, InspectionResult, LenientParseError, SpecialPurpose, StrictParseError};
+use serde::{Serialize, Serializer};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::io::{IsTerminal, Write};
+use std::net::IpAddr;
+use std::ops::Range;
 use tabled::{
     Table, Tabled,
-    settings::{Remove, Style, object::Rows},
+    settings::{Remove, Style, location::ByColumnName, object::Rows},
 };
 
-const JSON_OUTPUT_VERSION: u8 = 2;
+const JSON_OUTPUT_VERSION: u8 = 4;
+
+/// Table columns only shown with --classful
+pub(crate) const CLASSFUL_COLUMNS: &[&str] = &[
+    "class",
+    "classful_network",
+    "classful_mask",
+    "classful_boundary",
+];
+
+/// Table column only shown with --bits
+pub(crate) const BITS_COLUMN: &str = "bits";
+
+const NETWORK_BITS_COLOR: &str = "\x1b[32m";
+const HOST_BITS_COLOR: &str = "\x1b[33m";
+const RESET_COLOR: &str = "\x1b[0m";
 
 #[derive(Tabled, Default)]
 pub(crate) struct TableRow {
     ip_ver: &'static str,
     cidr: String,
     address: String,
-    prefix: u8,
+    prefix: String,
     network: String,
     first_usable: String,
     last_usable: String,
@@ -20,67 +40,537 @@ pub(crate) struct TableRow {
     available: String,
     netmask: String,
     hostmask: String,
+    global: String,
+    special_purpose: String,
+    class: String,
+    classful_network: String,
+    classful_mask: String,
+    classful_boundary: String,
+    bits: String,
+}
+
+/// Names of the special-purpose blocks with their RFCs e.g. "Private-Use (RFC 1918)",
+/// or "Private-Use (RFC 1918, partial)" for a block inside the network
+fn special_purpose_names(special_purpose: &[SpecialPurpose]) -> String {
+    special_purpose
+        .iter()
+        .map(|special_purpose| match special_purpose.partial {
+            true => format!(
+                "{} ({}, partial)",
+                special_purpose.name, special_purpose.rfc
+            ),
+            false => format!("{} ({})", special_purpose.name, special_purpose.rfc),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Network bits coloured apart from host bits, unless stdout is not a terminal or NO_COLOR is set
+fn colorize_binary(binary: &str) -> String {
+    if !std::io::stdout().is_terminal() || std::env::var_os("NO_COLOR").is_some() {
+        return binary.to_string();
+    }
+    let (network, host) = binary.split_once('|').unwrap_or((binary, ""));
+    format!("{NETWORK_BITS_COLOR}{network}{RESET_COLOR}|{HOST_BITS_COLOR}{host}{RESET_COLOR}")
+}
+
+/// One line per address or mask: binary, hex and integer
+fn render_bits(bits: &Bits) -> String {
+    [
+        ("address", &bits.address),
+        ("netmask", &bits.netmask),
+        ("hostmask", &bits.hostmask),
+        ("network", &bits.network),
+        ("last_address", &bits.last_address),
+    ]
+    .iter()
+    .map(|(name, address_bits)| {
+        format!(
+            "{name:<12}  {}  {}  {}",
+            colorize_binary(&address_bits.binary),
+            address_bits.hex,
+            address_bits.integer
+        )
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+fn display_or_empty(value: Option<impl Display>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
 }
 
 #[derive(Serialize)]
-struct JsonOutput {
+struct JsonOutput<T> {
     version: u8,
-    data: Vec<InspectionResult>,
+    data: T,
+}
+
+/// Serializes an iterator as a sequence without collecting it first
+struct Stream<I>(RefCell<Option<I>>);
+
+impl<I, T> Serialize for Stream<I>
+where
+    I: Iterator<Item = T>,
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.borrow_mut().take().into_iter().flatten())
+    }
+}
+
+/// Parse error that points at a part of its input
+pub(crate) trait Diagnostic: Display {
+    fn span(&self) -> Range<usize>;
+    fn expected(&self) -> String;
+    fn suggestion(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl Diagnostic for LenientParseError {
+    fn span(&self) -> Range<usize> {
+        LenientParseError::span(self)
+    }
+
+    fn expected(&self) -> String {
+        LenientParseError::expected(self)
+    }
+}
+
+impl Diagnostic for StrictParseError {
+    fn span(&self) -> Range<usize> {
+        StrictParseError::span(self)
+    }
+
+    fn expected(&self) -> String {
+        StrictParseError::expected(self)
+    }
+
+    fn suggestion(&self) -> Option<&str> {
+        StrictParseError::suggestion(self)
+    }
+}
+
+/// Input that could not be inspected, with the byte range of the problem
+#[derive(Serialize)]
+pub(crate) struct ErrorEntry {
+    pub(crate) error: String,
+    pub(crate) input: String,
+    pub(crate) offset: usize,
+    pub(crate) length: usize,
+    pub(crate) expected: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) suggestion: Option<String>,
+}
+
+impl ErrorEntry {
+    pub(crate) fn new(input: String, err: &dyn Diagnostic) -> Self {
+        let span = err.span();
+        ErrorEntry {
+            error: err.to_string(),
+            input,
+            offset: span.start,
+            length: span.len(),
+            expected: err.expected(),
+            suggestion: err.suggestion().map(String::from),
+        }
+    }
+}
+
+/// Renders a parse error rustc-style, with carets under the part of the input it points at
+pub(crate) fn render_parse_error(input: &str, err: &dyn Diagnostic) -> String {
+    let span = err.span();
+    let padding = input[..span.start].chars().count();
+    let width = input[span.clone()].chars().count().max(1);
+    let rendered = format!(
+        "error: {err}\n  |\n  | {input}\n  | {}{} expected {}",
+        " ".repeat(padding),
+        "^".repeat(width),
+        err.expected()
+    );
+    match err.suggestion() {
+        Some(suggestion) => format!("{rendered}\n  = help: write it as {suggestion}"),
+        None => rendered,
+    }
+}
+
+/// Inspection of one input, or the reason it failed (with --keep-going)
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(crate) enum InspectEntry {
+    Inspected(Box<InspectionResult>),
+    Failed(ErrorEntry),
+}
+
+#[derive(Tabled)]
+pub(crate) struct InspectTableRow {
+    #[tabled(inline)]
+    row: TableRow,
+    error: String,
+}
+
+impl From<InspectEntry> for InspectTableRow {
+    fn from(value: InspectEntry) -> Self {
+        match value {
+            InspectEntry::Inspected(inspection_result) => InspectTableRow {
+                row: TableRow::from(*inspection_result),
+                error: String::new(),
+            },
+            InspectEntry::Failed(error_entry) => InspectTableRow {
+                row: TableRow {
+                    ip_ver: "error",
+                    cidr: error_entry.input,
+                    ..Default::default()
+                },
+                error: match error_entry.suggestion {
+                    Some(suggestion) => format!(
+                        "{} at column {}, write it as {}",
+                        error_entry.error,
+                        error_entry.offset + 1,
+                        suggestion
+                    ),
+                    None => format!("{} at column {}", error_entry.error, error_entry.offset + 1),
+                },
+            },
+        }
+    }
+}
+
+/// Subnet of a VLSM plan, either allocated for a request or left free
+#[derive(Serialize)]
+pub(crate) struct PlanEntry {
+    pub(crate) status: &'static str,
+    pub(crate) name: Option<String>,
+    pub(crate) requested_hosts: Option<u128>,
+    #[serde(flatten)]
+    pub(crate) inspection_result: InspectionResult,
+}
+
+#[derive(Tabled)]
+pub(crate) struct PlanTableRow {
+    status: &'static str,
+    name: String,
+    requested_hosts: String,
+    #[tabled(inline)]
+    row: TableRow,
+}
+
+impl From<PlanEntry> for PlanTableRow {
+    fn from(value: PlanEntry) -> Self {
+        PlanTableRow {
+            status: value.status,
+            name: value.name.unwrap_or_default(),
+            requested_hosts: value
+                .requested_hosts
+                .map(|hosts| hosts.to_string())
+                .unwrap_or_default(),
+            row: TableRow::from(value.inspection_result),
+        }
+    }
+}
+
+/// Prefix of a lossy summary with the addresses it covers on top of the summarized networks
+#[derive(Serialize)]
+pub(crate) struct SummaryEntry {
+    pub(crate) extra_addresses: u128,
+    #[serde(flatten)]
+    pub(crate) inspection_result: InspectionResult,
+}
+
+#[derive(Tabled)]
+pub(crate) struct SummaryTableRow {
+    extra_addresses: u128,
+    #[tabled(inline)]
+    row: TableRow,
+}
+
+impl From<SummaryEntry> for SummaryTableRow {
+    fn from(value: SummaryEntry) -> Self {
+        SummaryTableRow {
+            extra_addresses: value.extra_addresses,
+            row: TableRow::from(value.inspection_result),
+        }
+    }
+}
+
+/// Answer to whether `candidate` falls inside `network`
+#[derive(Serialize, Tabled)]
+pub(crate) struct ContainsEntry {
+    pub(crate) network: String,
+    pub(crate) candidate: String,
+    pub(crate) contained: bool,
+}
+
+/// Pair of overlapping networks and how the first relates to the second
+#[derive(Serialize, Tabled)]
+pub(crate) struct ConflictEntry {
+    pub(crate) first: String,
+    pub(crate) second: String,
+    pub(crate) relation: String,
+}
+
+/// Reverse DNS zone covering part of `cidr`
+#[derive(Serialize)]
+pub(crate) struct ReverseZoneEntry {
+    pub(crate) cidr: String,
+    pub(crate) zone: String,
+    pub(crate) network: String,
+    /// Zone delegating this one with CNAMEs (RFC 2317)
+    pub(crate) parent: Option<String>,
+}
+
+#[derive(Tabled)]
+pub(crate) struct ReverseZoneTableRow {
+    cidr: String,
+    zone: String,
+    network: String,
+    parent: String,
+}
+
+impl From<ReverseZoneEntry> for ReverseZoneTableRow {
+    fn from(value: ReverseZoneEntry) -> Self {
+        ReverseZoneTableRow {
+            cidr: value.cidr,
+            zone: value.zone,
+            network: value.network,
+            parent: value.parent.unwrap_or_default(),
+        }
+    }
+}
+
+/// Usable address of a network
+#[derive(Serialize, Tabled)]
+pub(crate) struct HostEntry {
+    pub(crate) address: IpAddr,
+}
+
+/// Address at `position` of a network, or `position` addresses away from an address
+#[derive(Serialize, Tabled)]
+pub(crate) struct NthEntry {
+    pub(crate) position: i128,
+    pub(crate) address: IpAddr,
+}
+
+/// Network next to `cidr`. `network` is `None` when there is none e.g. past the end of the address space
+#[derive(Serialize)]
+pub(crate) struct NavEntry {
+    pub(crate) cidr: String,
+    /// parent, sibling, previous or next
+    pub(crate) relation: &'static str,
+    pub(crate) network: Option<String>,
+}
+
+#[derive(Tabled)]
+pub(crate) struct NavTableRow {
+    cidr: String,
+    relation: String,
+    network: String,
+}
+
+impl From<NavEntry> for NavTableRow {
+    fn from(value: NavEntry) -> Self {
+        NavTableRow {
+            cidr: value.cidr,
+            relation: value.relation.to_string(),
+            network: value.network.unwrap_or_default(),
+        }
+    }
+}
+
+/// Network an output entry stands for, written by the firewall formats
+pub(crate) trait ToCidr {
+    fn to_cidr(&self) -> Option<Cidr>;
+}
+
+impl ToCidr for InspectionResult {
+    fn to_cidr(&self) -> Option<Cidr> {
+        let (network, prefix_length) = match self {
+            InspectionResult::V4(v4) => (&v4.network, v4.prefix_length),
+            InspectionResult::V6(v6) => (&v6.network, v6.prefix_length),
+        };
+        format!("{network}/{prefix_length}").parse().ok()
+    }
+}
+
+impl ToCidr for InspectEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        match self {
+            InspectEntry::Inspected(inspection_result) => inspection_result.to_cidr(),
+            InspectEntry::Failed(_) => None,
+        }
+    }
+}
+
+impl ToCidr for PlanEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        self.inspection_result.to_cidr()
+    }
+}
+
+impl ToCidr for SummaryEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        self.inspection_result.to_cidr()
+    }
+}
+
+impl ToCidr for ContainsEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        None
+    }
+}
+
+impl ToCidr for ConflictEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        None
+    }
+}
+
+impl ToCidr for ReverseZoneEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        None
+    }
+}
+
+impl ToCidr for HostEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        Some(Cidr::from(self.address))
+    }
+}
+
+impl ToCidr for NthEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        Some(Cidr::from(self.address))
+    }
+}
+
+impl ToCidr for NavEntry {
+    fn to_cidr(&self) -> Option<Cidr> {
+        self.network.as_ref()?.parse().ok()
+    }
 }
 
 impl From<InspectionResult> for TableRow {
     fn from(value: InspectionResult) -> Self {
         match value {
             InspectionResult::V4(v4) => TableRow {
+                class: display_or_empty(v4.classful.as_ref().map(|classful| classful.class)),
+                classful_network: v4
+                    .classful
+                    .as_ref()
+                    .and_then(|classful| classful.network.clone())
+                    .unwrap_or_default(),
+                classful_mask: v4
+                    .classful
+                    .as_ref()
+                    .and_then(|classful| classful.default_mask.clone())
+                    .unwrap_or_default(),
+                classful_boundary: display_or_empty(
+                    v4.classful.as_ref().and_then(|classful| classful.boundary),
+                ),
                 ip_ver: "v4",
                 cidr: v4.cidr,
                 address: v4.address,
-                prefix: v4.prefix_length,
+                prefix: v4.prefix_length.to_string(),
                 first_usable: v4.first_usable,
                 last_usable: v4.last_usable,
                 network: v4.network,
-                broadcast: v4.broadcast,
-                ..Default::default()
+                broadcast: v4.broadcast.unwrap_or_default(),
+                available: v4.usable_addresses.to_string(),
+                netmask: v4.netmask,
+                hostmask: v4.hostmask,
+                global: v4.globally_reachable.to_string(),
+                special_purpose: special_purpose_names(&v4.special_purpose),
+                bits: v4.bits.as_ref().map(render_bits).unwrap_or_default(),
             },
             InspectionResult::V6(v6) => TableRow {
                 ip_ver: "v6",
                 cidr: v6.cidr,
                 address: v6.address,
-                prefix: v6.prefix_length,
+                prefix: v6.prefix_length.to_string(),
                 netmask: v6.netmask,
                 hostmask: v6.hostmask,
                 network: v6.network,
+                first_usable: v6.first_usable,
+                last_usable: v6.last_usable,
                 available: v6.subnet_size,
+                global: v6.globally_reachable.to_string(),
+                special_purpose: special_purpose_names(&v6.special_purpose),
+                bits: v6.bits.as_ref().map(render_bits).unwrap_or_default(),
                 ..Default::default()
             },
         }
     }
 }
 
-pub fn print_json(inspection_results: Vec<InspectionResult>, pretty: &bool) {
-    let json_output = JsonOutput {
-        version: JSON_OUTPUT_VERSION,
-        data: inspection_results,
-    };
+pub fn print_json<T: Serialize>(
+    items: impl Iterator<Item = T>,
+    pretty: &bool,
+) -> std::io::Result<()> {
+    write_json(
+        &JsonOutput {
+            version: JSON_OUTPUT_VERSION,
+            data: Stream(RefCell::new(Some(items))),
+        },
+        pretty,
+    )
+}
+
+/// Single report under a schema `version` of its own, for output that is not a list of entries
+pub fn print_json_report<T: Serialize>(
+    report: &T,
+    version: u8,
+    pretty: &bool,
+) -> std::io::Result<()> {
+    write_json(
+        &JsonOutput {
+            version,
+            data: report,
+        },
+        pretty,
+    )
+}
+
+fn write_json<T: Serialize>(json_output: &JsonOutput<T>, pretty: &bool) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
     match pretty {
-        true => println!("{}", serde_json::to_string_pretty(&json_output).unwrap()),
-        false => println!("{}", serde_json::to_string(&json_output).unwrap()),
+        true => serde_json::to_writer_pretty(&mut stdout, json_output)?,
+        false => serde_json::to_writer(&mut stdout, json_output)?,
     }
+    writeln!(stdout)
 }
 
-pub fn print_ndjson(inspection_results: Vec<InspectionResult>) {
-    inspection_results
-        .iter()
-        .for_each(|item| println!("{}", serde_json::to_string(&item).unwrap()));
+pub fn print_ndjson<T: Serialize>(items: impl Iterator<Item = T>) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    for item in items {
+        serde_json::to_writer(&mut stdout, &item)?;
+        writeln!(stdout)?;
+    }
+    Ok(())
 }
 
-pub fn print_table(results: Vec<InspectionResult>, headless: &bool) {
-    let rows: Vec<TableRow> = results.into_iter().map(TableRow::from).collect();
+pub fn print_lines(lines: impl Iterator<Item = String>) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    for line in lines {
+        writeln!(stdout, "{line}")?;
+    }
+    Ok(())
+}
+
+pub fn print_table<R: Tabled>(
+    rows: impl Iterator<Item = R>,
+    headless: &bool,
+    hidden_columns: &[&str],
+) -> std::io::Result<()> {
+    let rows: Vec<R> = rows.collect();
     let mut table = Table::new(rows);
     table.with(Style::blank());
+    for column in hidden_columns {
+        table.with(Remove::column(ByColumnName::new(column)));
+    }
     if *headless {
         table.with(Remove::row(Rows::first()));
     }
 
-    println!("{table}");
+    writeln!(std::io::stdout(), "{table}")
 }
@@ -0,0 +1,245 @@
+//! Address arithmetic shared by IPv4 and IPv6
+//!
+//! Addresses are widened to `u128` and networks are handled as `(first address, prefix length)`
+//! blocks, so the same code works for both families given the family's address width in bits
+
+pub(crate) const IPV4_BITS: u8 = 32;
+pub(crate) const IPV6_BITS: u8 = 128;
+
+/// Size of a block of `2^host_bits` addresses minus one, so a whole IPv6 space does not overflow
+pub(crate) fn block_size_minus_one(host_bits: u8) -> u128 {
+    u128::MAX
+        .checked_shr(128 - u32::from(host_bits))
+        .unwrap_or(0)
+}
+
+/// Last address of the block that starts at `start`
+pub(crate) fn block_last(start: u128, prefix: u8, bits: u8) -> u128 {
+    start + block_size_minus_one(bits - prefix)
+}
+
+/// Splits an inclusive address range into the minimal list of aligned `(start, prefix)` blocks
+pub(crate) fn range_to_blocks(mut start: u128, end: u128, bits: u8) -> Vec<(u128, u8)> {
+    let mut blocks = Vec::new();
+    while start <= end {
+        let alignment = (start.trailing_zeros() as u8).min(bits);
+        let host_bits = (0..=alignment)
+            .rev()
+            .find(|&host_bits| block_size_minus_one(host_bits) <= end - start)
+            .unwrap_or(0);
+        blocks.push((start, bits - host_bits));
+        match start.checked_add(block_size_minus_one(host_bits)) {
+            Some(last) if last < end => start = last + 1,
+            _ => break,
+        }
+    }
+    blocks
+}
+
+/// Sorts inclusive ranges and merges the overlapping and adjacent ones
+pub(crate) fn merge_ranges(mut ranges: Vec<(u128, u128)>) -> Vec<(u128, u128)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if last_end.checked_add(1).is_none_or(|next| start <= next) => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Intersection of two lists of sorted, disjoint ranges
+pub(crate) fn intersect_ranges(a: &[(u128, u128)], b: &[(u128, u128)]) -> Vec<(u128, u128)> {
+    let mut intersection = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start <= end {
+            intersection.push((start, end));
+        }
+        match a[i].1 < b[j].1 {
+            true => i += 1,
+            false => j += 1,
+        }
+    }
+    intersection
+}
+
+/// Ranges of `a` without the addresses of `b`. Both must be sorted and disjoint
+pub(crate) fn subtract_ranges(a: &[(u128, u128)], b: &[(u128, u128)]) -> Vec<(u128, u128)> {
+    let mut difference = Vec::new();
+    let mut j = 0;
+    for &(mut start, end) in a {
+        while j < b.len() && b[j].1 < start {
+            j += 1;
+        }
+        let mut k = j;
+        let mut exhausted = false;
+        while k < b.len() && b[k].0 <= end {
+            if b[k].0 > start {
+                difference.push((start, b[k].0 - 1));
+            }
+            match b[k].1.checked_add(1) {
+                Some(next) if next <= end => start = start.max(next),
+                _ => {
+                    exhausted = true;
+                    break;
+                }
+            }
+            k += 1;
+        }
+        if !exhausted {
+            difference.push((start, end));
+        }
+    }
+    difference
+}
+
+/// Smallest aligned `(start, prefix)` block that contains both addresses
+pub(crate) fn common_block(a: u128, b: u128, bits: u8) -> (u128, u8) {
+    let host_bits = (128 - (a ^ b).leading_zeros()) as u8;
+    (a & !block_size_minus_one(host_bits), bits - host_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        IPV4_BITS, IPV6_BITS, block_last, common_block, intersect_ranges, merge_ranges,
+        range_to_blocks, subtract_ranges,
+    };
+
+    #[test]
+    fn test_range_to_blocks_aligned() {
+        // Arrange
+        let expected_blocks: Vec<(u128, u8)> = vec![(0x0a000000, 24)];
+
+        // Act
+        let actual_blocks = range_to_blocks(0x0a000000, 0x0a0000ff, IPV4_BITS);
+
+        // Assert
+        assert_eq!(actual_blocks, expected_blocks);
+    }
+
+    #[test]
+    fn test_range_to_blocks_unaligned() {
+        // Arrange
+        // 10.0.0.1 - 10.0.0.77
+        let expected_blocks: Vec<(u128, u8)> = vec![
+            (0x0a000001, 32),
+            (0x0a000002, 31),
+            (0x0a000004, 30),
+            (0x0a000008, 29),
+            (0x0a000010, 28),
+            (0x0a000020, 27),
+            (0x0a000040, 29),
+            (0x0a000048, 30),
+            (0x0a00004c, 31),
+        ];
+
+        // Act
+        let actual_blocks = range_to_blocks(0x0a000001, 0x0a00004d, IPV4_BITS);
+
+        // Assert
+        assert_eq!(actual_blocks, expected_blocks);
+    }
+
+    #[test]
+    fn test_range_to_blocks_whole_space() {
+        // Arrange
+
+        // Act
+        let actual_v4_blocks = range_to_blocks(0, u32::MAX.into(), IPV4_BITS);
+        let actual_v6_blocks = range_to_blocks(0, u128::MAX, IPV6_BITS);
+
+        // Assert
+        assert_eq!(actual_v4_blocks, vec![(0, 0)]);
+        assert_eq!(actual_v6_blocks, vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_block_last() {
+        // Arrange
+
+        // Act
+        let actual_last = block_last(0x0a000000, 24, IPV4_BITS);
+
+        // Assert
+        assert_eq!(actual_last, 0x0a0000ff);
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        // Arrange
+        let expected_ranges: Vec<(u128, u128)> = vec![(0, 20), (30, u128::MAX)];
+
+        // Act
+        let actual_ranges = merge_ranges(vec![
+            (30, 40),
+            (0, 10),
+            (11, 15),
+            (5, 20),
+            (35, u128::MAX),
+            (u128::MAX, u128::MAX),
+        ]);
+
+        // Assert
+        assert_eq!(actual_ranges, expected_ranges);
+    }
+
+    #[test]
+    fn test_common_block() {
+        // Arrange
+
+        // Act
+        // 10.0.1.0 and 10.0.2.255
+        let actual_v4_block = common_block(0x0a000100, 0x0a0002ff, IPV4_BITS);
+        let actual_v6_block = common_block(0, u128::MAX, IPV6_BITS);
+
+        // Assert
+        assert_eq!(actual_v4_block, (0x0a000000, 22));
+        assert_eq!(actual_v6_block, (0, 0));
+    }
+
+    #[test]
+    fn test_intersect_ranges() {
+        // Arrange
+        let expected_ranges: Vec<(u128, u128)> = vec![(5, 10), (20, 20), (30, 35)];
+
+        // Act
+        let actual_ranges = intersect_ranges(&[(0, 10), (20, 35)], &[(5, 20), (30, 40)]);
+
+        // Assert
+        assert_eq!(actual_ranges, expected_ranges);
+    }
+
+    #[test]
+    fn test_subtract_ranges() {
+        // Arrange
+        let expected_ranges: Vec<(u128, u128)> = vec![(0, 4), (11, 14), (41, 50)];
+
+        // Act
+        let actual_ranges = subtract_ranges(
+            &[(0, 20), (30, 50), (60, 70)],
+            &[(5, 10), (15, 40), (55, 80)],
+        );
+
+        // Assert
+        assert_eq!(actual_ranges, expected_ranges);
+    }
+
+    #[test]
+    fn test_subtract_ranges_to_the_end() {
+        // Arrange
+        let expected_ranges: Vec<(u128, u128)> = vec![(0, 9)];
+
+        // Act
+        let actual_ranges = subtract_ranges(&[(0, u128::MAX)], &[(10, u128::MAX)]);
+
+        // Assert
+        assert_eq!(actual_ranges, expected_ranges);
+    }
+}
@@ -0,0 +1,171 @@
+//! Pool utilization report
+
+use cidit::Utilization;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tabled::Tabled;
+
+/// Version of the JSON schema of [`UtilizationReport`], bumped on breaking changes
+pub(crate) const UTILIZATION_OUTPUT_VERSION: u8 = 1;
+
+/// Widest bar of the histogram, in characters
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Allocations and aligned free blocks of one prefix length
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub(crate) struct PrefixCount {
+    pub(crate) prefix: u8,
+    pub(crate) allocated: usize,
+    pub(crate) free: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct UtilizationReport {
+    pub(crate) pool: String,
+    pub(crate) total_addresses: u128,
+    pub(crate) used_addresses: u128,
+    pub(crate) free_addresses: u128,
+    pub(crate) utilization_percent: f64,
+    pub(crate) largest_free_block: Option<String>,
+    pub(crate) free_fragments: usize,
+    /// Allocations not entirely inside the pool
+    pub(crate) outside: Vec<String>,
+    /// Prefix lengths with allocations or free blocks, shortest first
+    pub(crate) prefixes: Vec<PrefixCount>,
+}
+
+impl From<Utilization> for UtilizationReport {
+    fn from(value: Utilization) -> Self {
+        let mut prefixes: BTreeMap<u8, PrefixCount> = BTreeMap::new();
+        for (prefix, allocated) in &value.allocated_by_prefix {
+            prefixes.entry(*prefix).or_default().allocated = *allocated;
+        }
+        for (prefix, free) in &value.free_by_prefix {
+            prefixes.entry(*prefix).or_default().free = *free;
+        }
+        UtilizationReport {
+            pool: value.pool.to_string(),
+            total_addresses: value.total_addresses,
+            used_addresses: value.used_addresses,
+            free_addresses: value.free_addresses,
+            utilization_percent: value.percent(),
+            largest_free_block: value.largest_free_block.map(|cidr| cidr.to_string()),
+            free_fragments: value.free_fragments,
+            outside: value.outside.iter().map(|cidr| cidr.to_string()).collect(),
+            prefixes: prefixes
+                .into_iter()
+                .map(|(prefix, count)| PrefixCount { prefix, ..count })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+pub(crate) struct UtilizationTableRow {
+    pool: String,
+    total: u128,
+    used: u128,
+    free: u128,
+    utilization: String,
+    largest_free_block: String,
+    free_fragments: usize,
+}
+
+impl From<&UtilizationReport> for UtilizationTableRow {
+    fn from(value: &UtilizationReport) -> Self {
+        UtilizationTableRow {
+            pool: value.pool.clone(),
+            total: value.total_addresses,
+            used: value.used_addresses,
+            free: value.free_addresses,
+            utilization: format!("{:.2}%", value.utilization_percent),
+            largest_free_block: value.largest_free_block.clone().unwrap_or_default(),
+            free_fragments: value.free_fragments,
+        }
+    }
+}
+
+/// Text histogram of allocations and free blocks by prefix length, bars scaled to the largest count
+pub(crate) fn histogram(report: &UtilizationReport) -> Vec<String> {
+    let max = report
+        .prefixes
+        .iter()
+        .map(|count| count.allocated.max(count.free))
+        .max()
+        .unwrap_or(0);
+    let section = |title: &str, counts: Vec<(u8, usize)>| {
+        let bars = counts
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(prefix, count)| {
+                let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(max));
+                format!("  /{prefix:<3} {bar:<HISTOGRAM_WIDTH$} {count}")
+            });
+        std::iter::once(title.to_string())
+            .chain(bars)
+            .collect::<Vec<_>>()
+    };
+    let mut lines = section(
+        "allocated",
+        report
+            .prefixes
+            .iter()
+            .map(|count| (count.prefix, count.allocated))
+            .collect(),
+    );
+    lines.extend(section(
+        "free",
+        report
+            .prefixes
+            .iter()
+            .map(|count| (count.prefix, count.free))
+            .collect(),
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PrefixCount, UtilizationReport, histogram};
+    use cidit::Cidr;
+
+    #[test]
+    fn test_report_and_histogram() {
+        // Arrange
+        let expected_pool: Cidr = "10.0.0.0/22".parse().unwrap();
+        let expected_allocations: Vec<Cidr> = ["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/25"]
+            .iter()
+            .map(|cidr| cidr.parse().unwrap())
+            .collect();
+        let expected_prefixes = vec![
+            PrefixCount {
+                prefix: 24,
+                allocated: 2,
+                free: 1,
+            },
+            PrefixCount {
+                prefix: 25,
+                allocated: 1,
+                free: 1,
+            },
+        ];
+        let expected_histogram = vec![
+            "allocated".to_string(),
+            format!("  /24  {:<40} 2", "#".repeat(40)),
+            format!("  /25  {:<40} 1", "#".repeat(20)),
+            "free".to_string(),
+            format!("  /24  {:<40} 1", "#".repeat(20)),
+            format!("  /25  {:<40} 1", "#".repeat(20)),
+        ];
+
+        // Act
+        let actual_report =
+            UtilizationReport::from(expected_pool.utilization(&expected_allocations));
+        let actual_histogram = histogram(&actual_report);
+
+        // Assert
+        assert_eq!(actual_report.utilization_percent, 62.5);
+        assert_eq!(actual_report.prefixes, expected_prefixes);
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+}
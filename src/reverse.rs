@@ -0,0 +1,189 @@
+//! Reverse DNS zones under in-addr.arpa and ip6.arpa
+
+use crate::cidr::Cidr;
+use std::net::IpAddr;
+
+/// Last IPv4 prefix length that still has a zone of its own. Longer ones are delegated as in RFC 2317
+const LAST_OCTET_ZONE_PREFIX_LEN: u8 = 24;
+
+/// Reverse DNS zone covering part of a network
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReverseZone {
+    /// Zone name e.g. 2.0.192.in-addr.arpa
+    pub name: String,
+    /// Part of the network the zone covers
+    pub network: Cidr,
+    /// Zone that delegates this one with CNAMEs, for RFC 2317 classless delegations
+    pub parent: Option<String>,
+}
+
+impl ReverseZone {
+    /// Owner name of the PTR record of `addr`, relative to the zone its records are written in
+    /// e.g. 65 in 2.0.192.in-addr.arpa. `None` if the zone does not cover `addr`
+    pub fn relative_name(&self, addr: IpAddr) -> Option<String> {
+        if !self.network.contains(addr) {
+            return None;
+        }
+        let (value, bits) = match addr {
+            IpAddr::V4(v4) => (u128::from(u32::from(v4)), 32),
+            IpAddr::V6(v6) => (u128::from(v6), 128),
+        };
+        let zone = self.parent.as_ref().unwrap_or(&self.name);
+        zone_name(value, bits, bits)
+            .strip_suffix(&format!(".{zone}"))
+            .map(String::from)
+    }
+}
+
+/// Name of the zone of the first `prefix` bits of `start`, one label per octet (IPv4) or nibble (IPv6)
+fn zone_name(start: u128, prefix: u8, bits: u8) -> String {
+    let (step, suffix) = match bits {
+        32 => (8, "in-addr.arpa"),
+        _ => (4, "ip6.arpa"),
+    };
+    let labels = (0..prefix / step).rev().map(|index| {
+        let label = start >> (bits - step * (index + 1)) & ((1 << step) - 1);
+        match bits {
+            32 => label.to_string(),
+            _ => format!("{label:x}"),
+        }
+    });
+    labels
+        .chain(std::iter::once(suffix.to_string()))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+impl Cidr {
+    /// Reverse DNS zones covering the network
+    ///
+    /// Prefixes that are not on an octet (IPv4) or nibble (IPv6) boundary are covered by the zones of the
+    /// next boundary. IPv4 networks longer than /24 get an [RFC 2317](https://www.rfc-editor.org/rfc/rfc2317)
+    /// classless delegation name instead
+    ///
+    /// # Example
+    /// ```
+    /// use cidit::Cidr;
+    ///
+    /// let cidr: Cidr = "192.0.2.64/26".parse().unwrap();
+    ///
+    /// let actual_zones = cidr.reverse_zones();
+    ///
+    /// assert_eq!(actual_zones[0].name, "64/26.2.0.192.in-addr.arpa");
+    /// assert_eq!(actual_zones[0].parent.as_deref(), Some("2.0.192.in-addr.arpa"));
+    /// ```
+    pub fn reverse_zones(&self) -> Vec<ReverseZone> {
+        let (start, _) = self.range();
+        let (prefix, bits) = (self.prefix_len(), self.bits());
+        if bits == 32 && prefix > LAST_OCTET_ZONE_PREFIX_LEN {
+            let parent = zone_name(start, LAST_OCTET_ZONE_PREFIX_LEN, bits);
+            return vec![ReverseZone {
+                name: format!("{}/{}.{}", start & 0xff, prefix, parent),
+                network: Cidr::from_block(start, prefix, bits),
+                parent: Some(parent),
+            }];
+        }
+        let step = match bits {
+            32 => 8,
+            _ => 4,
+        };
+        let zone_prefix = prefix.div_ceil(step) * step;
+        (0..1u128 << (zone_prefix - prefix))
+            .map(|index| {
+                let zone_start =
+                    start + index.checked_shl((bits - zone_prefix).into()).unwrap_or(0);
+                ReverseZone {
+                    name: zone_name(zone_start, zone_prefix, bits),
+                    network: Cidr::from_block(zone_start, zone_prefix, bits),
+                    parent: None,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cidr::Cidr;
+
+    #[test]
+    fn test_reverse_zones() {
+        // Arrange
+        let expected_zones = [
+            ("10.1.0.0/16", vec!["1.10.in-addr.arpa"]),
+            ("192.0.2.7/24", vec!["2.0.192.in-addr.arpa"]),
+            (
+                "10.22.132.0/22",
+                vec![
+                    "132.22.10.in-addr.arpa",
+                    "133.22.10.in-addr.arpa",
+                    "134.22.10.in-addr.arpa",
+                    "135.22.10.in-addr.arpa",
+                ],
+            ),
+            ("192.0.2.128/25", vec!["128/25.2.0.192.in-addr.arpa"]),
+            ("192.0.2.5/32", vec!["5/32.2.0.192.in-addr.arpa"]),
+            ("0.0.0.0/0", vec!["in-addr.arpa"]),
+            ("2001:db8::/32", vec!["8.b.d.0.1.0.0.2.ip6.arpa"]),
+            (
+                "2001:db8::/31",
+                vec!["8.b.d.0.1.0.0.2.ip6.arpa", "9.b.d.0.1.0.0.2.ip6.arpa"],
+            ),
+            ("::/0", vec!["ip6.arpa"]),
+        ];
+
+        for (cidr, expected_names) in expected_zones {
+            // Act
+            let actual_names: Vec<String> = cidr
+                .parse::<Cidr>()
+                .unwrap()
+                .reverse_zones()
+                .into_iter()
+                .map(|zone| zone.name)
+                .collect();
+
+            // Assert
+            assert_eq!(actual_names, expected_names, "{cidr}");
+        }
+    }
+
+    #[test]
+    fn test_relative_name() {
+        // Arrange
+        let expected_names = [
+            ("10.22.132.0/22", "10.22.132.7", Some("7")),
+            ("192.0.2.64/26", "192.0.2.65", Some("65")),
+            ("192.0.2.64/26", "192.0.2.1", None),
+            (
+                "2001:db8::/32",
+                "2001:db8::1",
+                Some("1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0"),
+            ),
+        ];
+
+        for (cidr, addr, expected_name) in expected_names {
+            let zone = cidr.parse::<Cidr>().unwrap().reverse_zones().remove(0);
+
+            // Act
+            let actual_name = zone.relative_name(addr.parse().unwrap());
+
+            // Assert
+            assert_eq!(actual_name.as_deref(), expected_name, "{addr}");
+        }
+    }
+
+    #[test]
+    fn test_reverse_zones_cover_the_network() {
+        // Arrange
+        let expected_cidr: Cidr = "2001:db8::/62".parse().unwrap();
+
+        // Act
+        let actual_zones = expected_cidr.reverse_zones();
+
+        // Assert
+        assert_eq!(actual_zones.len(), 4);
+        assert!(actual_zones.iter().all(
+            |zone| zone.network.is_subnet_of(&expected_cidr) && zone.network.prefix_len() == 64
+        ));
+    }
+}
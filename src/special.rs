@@ -0,0 +1,263 @@
+//! IANA special-purpose address blocks
+//!
+//! Based on the [IPv4](https://www.iana.org/assignments/iana-ipv4-special-registry/) and
+//! [IPv6](https://www.iana.org/assignments/iana-ipv6-special-registry/) Special-Purpose Address Registries.
+//! Multicast is added from RFC 5771 and RFC 4291. Blocks the registries mark as deprecated or
+//! with no answer on global reachability are left out
+
+use crate::cidr::Cidr;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+/// Special-purpose block that an inspected network falls in or covers part of
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct SpecialPurpose {
+    pub block: String,
+    pub name: String,
+    pub rfc: String,
+    pub globally_reachable: bool,
+    /// The block lies inside the network, so it only covers part of its addresses
+    pub partial: bool,
+}
+
+/// `(block, name, rfc, globally reachable)`
+const BLOCKS: &[(&str, &str, &str, bool)] = &[
+    ("0.0.0.0/8", "This network", "RFC 791", false),
+    ("10.0.0.0/8", "Private-Use", "RFC 1918", false),
+    (
+        "100.64.0.0/10",
+        "Shared Address Space (CGNAT)",
+        "RFC 6598",
+        false,
+    ),
+    ("127.0.0.0/8", "Loopback", "RFC 1122", false),
+    ("169.254.0.0/16", "Link Local", "RFC 3927", false),
+    ("172.16.0.0/12", "Private-Use", "RFC 1918", false),
+    (
+        "192.0.0.0/24",
+        "IETF Protocol Assignments",
+        "RFC 6890",
+        false,
+    ),
+    (
+        "192.0.0.0/29",
+        "IPv4 Service Continuity Prefix",
+        "RFC 7335",
+        false,
+    ),
+    ("192.0.0.8/32", "IPv4 dummy address", "RFC 7600", false),
+    (
+        "192.0.0.9/32",
+        "Port Control Protocol Anycast",
+        "RFC 7723",
+        true,
+    ),
+    (
+        "192.0.0.10/32",
+        "Traversal Using Relays around NAT Anycast",
+        "RFC 8155",
+        true,
+    ),
+    ("192.0.0.170/32", "NAT64/DNS64 Discovery", "RFC 8880", false),
+    ("192.0.0.171/32", "NAT64/DNS64 Discovery", "RFC 8880", false),
+    (
+        "192.0.2.0/24",
+        "Documentation (TEST-NET-1)",
+        "RFC 5737",
+        false,
+    ),
+    ("192.31.196.0/24", "AS112-v4", "RFC 7535", true),
+    ("192.52.193.0/24", "AMT", "RFC 7450", true),
+    ("192.168.0.0/16", "Private-Use", "RFC 1918", false),
+    (
+        "192.175.48.0/24",
+        "Direct Delegation AS112 Service",
+        "RFC 7534",
+        true,
+    ),
+    ("198.18.0.0/15", "Benchmarking", "RFC 2544", false),
+    (
+        "198.51.100.0/24",
+        "Documentation (TEST-NET-2)",
+        "RFC 5737",
+        false,
+    ),
+    (
+        "203.0.113.0/24",
+        "Documentation (TEST-NET-3)",
+        "RFC 5737",
+        false,
+    ),
+    ("224.0.0.0/4", "Multicast", "RFC 5771", false),
+    ("240.0.0.0/4", "Reserved", "RFC 1112", false),
+    ("255.255.255.255/32", "Limited Broadcast", "RFC 919", false),
+    ("::/128", "Unspecified Address", "RFC 4291", false),
+    ("::1/128", "Loopback Address", "RFC 4291", false),
+    ("::ffff:0:0/96", "IPv4-mapped Address", "RFC 4291", false),
+    ("64:ff9b::/96", "IPv4-IPv6 Translation", "RFC 6052", true),
+    (
+        "64:ff9b:1::/48",
+        "Local-use IPv4/IPv6 Translation",
+        "RFC 8215",
+        false,
+    ),
+    ("100::/64", "Discard-Only Address Block", "RFC 6666", false),
+    ("2001::/23", "IETF Protocol Assignments", "RFC 2928", false),
+    (
+        "2001:1::1/128",
+        "Port Control Protocol Anycast",
+        "RFC 7723",
+        true,
+    ),
+    (
+        "2001:1::2/128",
+        "Traversal Using Relays around NAT Anycast",
+        "RFC 8155",
+        true,
+    ),
+    ("2001:2::/48", "Benchmarking", "RFC 5180", false),
+    ("2001:3::/32", "AMT", "RFC 7450", true),
+    ("2001:4:112::/48", "AS112-v6", "RFC 7535", true),
+    ("2001:20::/28", "ORCHIDv2", "RFC 7343", true),
+    ("2001:db8::/32", "Documentation", "RFC 3849", false),
+    (
+        "2620:4f:8000::/48",
+        "Direct Delegation AS112 Service",
+        "RFC 7534",
+        true,
+    ),
+    ("3fff::/20", "Documentation", "RFC 9637", false),
+    (
+        "5f00::/16",
+        "Segment Routing (SRv6) SIDs",
+        "RFC 9602",
+        false,
+    ),
+    ("fc00::/7", "Unique-Local", "RFC 4193", false),
+    ("fe80::/10", "Link-Local Unicast", "RFC 4291", false),
+    ("ff00::/8", "Multicast", "RFC 4291", false),
+];
+
+static PARSED_BLOCKS: LazyLock<Vec<(Cidr, SpecialPurpose)>> = LazyLock::new(|| {
+    BLOCKS
+        .iter()
+        .map(|&(block, name, rfc, globally_reachable)| {
+            (
+                block.parse().expect("valid special-purpose block"),
+                SpecialPurpose {
+                    block: block.to_string(),
+                    name: name.to_string(),
+                    rfc: rfc.to_string(),
+                    globally_reachable,
+                    partial: false,
+                },
+            )
+        })
+        .collect()
+});
+
+/// Special-purpose blocks overlapping `cidr`, widest first. Blocks inside `cidr` are marked `partial`
+pub(crate) fn special_purpose(cidr: &Cidr) -> Vec<SpecialPurpose> {
+    let mut blocks: Vec<&(Cidr, SpecialPurpose)> = PARSED_BLOCKS
+        .iter()
+        .filter(|(block, _)| block.overlaps(cidr))
+        .collect();
+    blocks.sort_by_key(|(block, _)| block.prefix_len());
+    blocks
+        .into_iter()
+        .map(|(block, special_purpose)| SpecialPurpose {
+            partial: !block.is_supernet_of(cidr),
+            ..special_purpose.clone()
+        })
+        .collect()
+}
+
+/// Whether all addresses of a network are globally reachable, given the blocks overlapping it widest first.
+/// Of the blocks containing the network the most specific one decides, as in the registries,
+/// and any block covering part of it that is not globally reachable makes it `false`
+pub(crate) fn globally_reachable(special_purpose: &[SpecialPurpose]) -> bool {
+    let (partial, containing): (Vec<&SpecialPurpose>, Vec<&SpecialPurpose>) = special_purpose
+        .iter()
+        .partition(|special_purpose| special_purpose.partial);
+    containing
+        .last()
+        .is_none_or(|special_purpose| special_purpose.globally_reachable)
+        && partial
+            .iter()
+            .all(|special_purpose| special_purpose.globally_reachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{globally_reachable, special_purpose};
+    use crate::cidr::Cidr;
+
+    #[test]
+    fn test_special_purpose_blocks() {
+        // Arrange
+        let expected_blocks = [
+            ("10.1.0.0/16", vec!["10.0.0.0/8 RFC 1918"]),
+            ("100.100.0.1/32", vec!["100.64.0.0/10 RFC 6598"]),
+            ("192.0.2.0/25", vec!["192.0.2.0/24 RFC 5737"]),
+            ("169.254.1.1/32", vec!["169.254.0.0/16 RFC 3927"]),
+            (
+                "192.0.0.0/30",
+                vec!["192.0.0.0/24 RFC 6890", "192.0.0.0/29 RFC 7335"],
+            ),
+            ("fd00::/8", vec!["fc00::/7 RFC 4193"]),
+            ("fe80::1/64", vec!["fe80::/10 RFC 4291"]),
+            ("2001:db8:1::/48", vec!["2001:db8::/32 RFC 3849"]),
+            ("64:ff9b::10.0.0.1/128", vec!["64:ff9b::/96 RFC 6052"]),
+            ("8.8.8.0/24", vec![]),
+            ("10.0.0.0/7", vec!["10.0.0.0/8 RFC 1918 partial"]),
+            (
+                "192.0.0.8/30",
+                vec![
+                    "192.0.0.0/24 RFC 6890",
+                    "192.0.0.8/32 RFC 7600 partial",
+                    "192.0.0.9/32 RFC 7723 partial",
+                    "192.0.0.10/32 RFC 8155 partial",
+                ],
+            ),
+        ];
+
+        for (cidr, expected_names) in expected_blocks {
+            // Act
+            let actual_names: Vec<String> = special_purpose(&cidr.parse::<Cidr>().unwrap())
+                .into_iter()
+                .map(|special_purpose| match special_purpose.partial {
+                    true => format!("{} {} partial", special_purpose.block, special_purpose.rfc),
+                    false => format!("{} {}", special_purpose.block, special_purpose.rfc),
+                })
+                .collect();
+
+            // Assert
+            assert_eq!(actual_names, expected_names, "{cidr}");
+        }
+    }
+
+    #[test]
+    fn test_globally_reachable() {
+        // Arrange
+        let expected_reachability = [
+            ("8.8.8.8/32", true),
+            ("10.0.0.0/24", false),
+            ("192.0.0.0/29", false),
+            ("192.0.0.9/32", true),
+            ("2001:4860::/32", true),
+            ("64:ff9b::/96", true),
+            ("0.0.0.0/0", false),
+            ("10.0.0.0/7", false),
+            ("192.31.196.0/23", true),
+            ("::/0", false),
+        ];
+
+        for (cidr, expected_reachable) in expected_reachability {
+            // Act
+            let actual_reachable = globally_reachable(&special_purpose(&cidr.parse().unwrap()));
+
+            // Assert
+            assert_eq!(actual_reachable, expected_reachable, "{cidr}");
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SplitError {
+    #[error("Invalid subnet prefix: /{new} (expected /{current} to /{max})")]
+    InvalidPrefix { new: u8, current: u8, max: u8 },
+    #[error("Invalid subnet count: {0} (expected a power of two)")]
+    NotPowerOfTwo(u128),
+    #[error("Cannot split a /{prefix} into {count} subnets")]
+    TooManySubnets { count: u128, prefix: u8 },
+}
+
+/// How to split a network into equal subnets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    /// Subnets with the given prefix length, e.g. `/24` children of a `/16`
+    Prefix(u8),
+    /// The given number of equal subnets. Must be a power of two
+    Count(u128),
+}
+
+impl SplitBy {
+    /// Resolves the prefix length of the subnets for a network with `current` prefix length
+    pub(crate) fn prefix_len(&self, current: u8, max: u8) -> Result<u8, SplitError> {
+        match *self {
+            SplitBy::Prefix(new) if new < current || new > max => {
+                Err(SplitError::InvalidPrefix { new, current, max })
+            }
+            SplitBy::Prefix(new) => Ok(new),
+            SplitBy::Count(count) if !count.is_power_of_two() => {
+                Err(SplitError::NotPowerOfTwo(count))
+            }
+            SplitBy::Count(count) => {
+                let extra_bits = count.trailing_zeros() as u8;
+                match current.checked_add(extra_bits) {
+                    Some(new) if new <= max => Ok(new),
+                    _ => Err(SplitError::TooManySubnets {
+                        count,
+                        prefix: current,
+                    }),
+                }
+            }
+        }
+    }
+}
+
+/// Trait that provides `split` function
+///
+/// Subnets are produced lazily, so splitting into a huge number of subnets is cheap
+/// as long as the caller does not collect them
+///
+/// # Example
+/// ```
+/// use cidit::{Ipv4Cidr, SplitBy, Splittable};
+///
+/// let expected_ipv4_cidr: Ipv4Cidr = "10.0.0.0/16".parse().unwrap();
+///
+/// let actual_subnets: Vec<Ipv4Cidr> = expected_ipv4_cidr.split(SplitBy::Prefix(24)).unwrap().collect();
+///
+/// assert_eq!(actual_subnets.len(), 256);
+/// assert_eq!(actual_subnets[1], "10.0.1.0/24".parse().unwrap());
+/// ```
+pub trait Splittable: Sized {
+    type Subnets: Iterator<Item = Self>;
+
+    fn split(&self, by: SplitBy) -> Result<Self::Subnets, SplitError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SplitBy, SplitError};
+
+    #[test]
+    fn test_prefix_len_by_prefix() {
+        // Arrange
+        let expected_split_by = SplitBy::Prefix(24);
+
+        // Act
+        let actual_prefix_len = expected_split_by.prefix_len(16, 32);
+
+        // Assert
+        assert_eq!(actual_prefix_len, Ok(24));
+    }
+
+    #[test]
+    fn test_prefix_len_by_shorter_prefix() {
+        // Arrange
+        let expected_split_by = SplitBy::Prefix(8);
+
+        // Act
+        let actual_prefix_len = expected_split_by.prefix_len(16, 32);
+
+        // Assert
+        assert_eq!(
+            actual_prefix_len,
+            Err(SplitError::InvalidPrefix {
+                new: 8,
+                current: 16,
+                max: 32
+            })
+        );
+    }
+
+    #[test]
+    fn test_prefix_len_by_count() {
+        // Arrange
+        let expected_split_by = SplitBy::Count(16);
+
+        // Act
+        let actual_prefix_len = expected_split_by.prefix_len(48, 128);
+
+        // Assert
+        assert_eq!(actual_prefix_len, Ok(52));
+    }
+
+    #[test]
+    fn test_prefix_len_by_count_not_power_of_two() {
+        // Arrange
+        let expected_split_by = SplitBy::Count(12);
+
+        // Act
+        let actual_prefix_len = expected_split_by.prefix_len(48, 128);
+
+        // Assert
+        assert_eq!(actual_prefix_len, Err(SplitError::NotPowerOfTwo(12)));
+    }
+
+    #[test]
+    fn test_prefix_len_by_count_too_many() {
+        // Arrange
+        let expected_split_by = SplitBy::Count(8);
+
+        // Act
+        let actual_prefix_len = expected_split_by.prefix_len(30, 32);
+
+        // Assert
+        assert_eq!(
+            actual_prefix_len,
+            Err(SplitError::TooManySubnets {
+                count: 8,
+                prefix: 30
+            })
+        );
+    }
+}
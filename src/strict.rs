@@ -0,0 +1,190 @@
+use crate::cidr::{Cidr, CidrParseError};
+use std::ops::Range;
+use thiserror::Error;
+
+/// Why a string is not a CIDR in canonical form. Offsets are byte offsets into the parsed string
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum StrictParseError {
+    #[error(transparent)]
+    Cidr(#[from] CidrParseError),
+    #[error("'{value}' has leading zeros")]
+    LeadingZeros {
+        value: String,
+        offset: usize,
+        suggestion: String,
+    },
+    #[error("'{value}' is not in RFC 5952 canonical form")]
+    NonCanonicalIpv6 { value: String, suggestion: String },
+    #[error("host bits are set")]
+    HostBitsSet { length: usize, suggestion: String },
+}
+
+impl StrictParseError {
+    /// Bytes of the input the error points at. Empty when something is missing at its start
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            StrictParseError::Cidr(error) => error.span(),
+            StrictParseError::LeadingZeros { value, offset, .. } => *offset..*offset + value.len(),
+            StrictParseError::NonCanonicalIpv6 { value, .. } => 0..value.len(),
+            StrictParseError::HostBitsSet { length, .. } => 0..*length,
+        }
+    }
+
+    /// What was expected at the span
+    pub fn expected(&self) -> String {
+        match self {
+            StrictParseError::Cidr(error) => error.expected(),
+            StrictParseError::LeadingZeros { value, .. } => match value.trim_start_matches('0') {
+                "" => "0".to_string(),
+                trimmed => trimmed.to_string(),
+            },
+            StrictParseError::NonCanonicalIpv6 { .. } => {
+                "lowercase groups without leading zeros, with the longest run of zeros as ::"
+                    .to_string()
+            }
+            StrictParseError::HostBitsSet { .. } => "the network address".to_string(),
+        }
+    }
+
+    /// Canonical form of the input, when only its notation was rejected
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            StrictParseError::Cidr(_) => None,
+            StrictParseError::LeadingZeros { suggestion, .. }
+            | StrictParseError::NonCanonicalIpv6 { suggestion, .. }
+            | StrictParseError::HostBitsSet { suggestion, .. } => Some(suggestion),
+        }
+    }
+}
+
+/// Octets and the prefix of `s` with their offsets
+fn numbers(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.split(['.', '/']).scan(0, |offset, number| {
+        let start = *offset;
+        *offset += number.len() + 1;
+        Some((start, number))
+    })
+}
+
+impl Cidr {
+    /// Parses a CIDR only if it is written in canonical form
+    ///
+    /// Rejects host bits set, leading zeros and IPv6 text that is not in
+    /// [RFC 5952](https://www.rfc-editor.org/rfc/rfc5952) form. Each rejection suggests the canonical network
+    ///
+    /// # Example
+    /// ```
+    /// use cidit::Cidr;
+    ///
+    /// let actual_error = Cidr::parse_strict("10.22.135.144/24").unwrap_err();
+    ///
+    /// assert!(Cidr::parse_strict("10.22.135.0/24").is_ok());
+    /// assert_eq!(actual_error.suggestion(), Some("10.22.135.0/24"));
+    /// ```
+    pub fn parse_strict(s: &str) -> Result<Cidr, StrictParseError> {
+        let cidr: Cidr = s.parse()?;
+        let (first, _) = cidr.range();
+        let suggestion = Cidr::from_block(first, cidr.prefix_len(), cidr.bits()).to_string();
+        let (address, _) = s.split_once('/').unwrap_or((s, ""));
+
+        match cidr {
+            Cidr::V4(_) => {
+                if let Some((offset, value)) =
+                    numbers(s).find(|(_, number)| number.len() > 1 && number.starts_with('0'))
+                {
+                    return Err(StrictParseError::LeadingZeros {
+                        value: value.to_string(),
+                        offset,
+                        suggestion,
+                    });
+                }
+            }
+            Cidr::V6(v6) => {
+                if address != v6.addr().to_string() {
+                    return Err(StrictParseError::NonCanonicalIpv6 {
+                        value: address.to_string(),
+                        suggestion,
+                    });
+                }
+                let prefix_offset = address.len() + 1;
+                let prefix = &s[prefix_offset..];
+                if prefix.len() > 1 && prefix.starts_with('0') {
+                    return Err(StrictParseError::LeadingZeros {
+                        value: prefix.to_string(),
+                        offset: prefix_offset,
+                        suggestion,
+                    });
+                }
+            }
+        }
+
+        if cidr.to_string() != suggestion {
+            return Err(StrictParseError::HostBitsSet {
+                length: address.len(),
+                suggestion,
+            });
+        }
+        Ok(cidr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StrictParseError;
+    use crate::cidr::Cidr;
+
+    #[test]
+    fn test_parse_strict_accepts_canonical() {
+        // Arrange
+        let expected_cidrs = [
+            "10.0.0.0/8",
+            "0.0.0.0/0",
+            "2001:db8::/32",
+            "::ffff:10.0.0.0/104",
+        ];
+
+        for expected_cidr in expected_cidrs {
+            // Act
+            let actual_cidr = Cidr::parse_strict(expected_cidr).unwrap();
+
+            // Assert
+            assert_eq!(actual_cidr.to_string(), expected_cidr);
+        }
+    }
+
+    #[test]
+    fn test_parse_strict_rejections_suggest_a_fix() {
+        // Arrange
+        let expected_rejections = [
+            ("10.22.135.144/24", 0..13, "10.22.135.0/24"),
+            ("010.1.1.0/24", 0..3, "10.1.1.0/24"),
+            ("10.1.1.0/024", 9..12, "10.1.1.0/24"),
+            ("2001:DB8::/32", 0..10, "2001:db8::/32"),
+            ("2001:0db8::/32", 0..11, "2001:db8::/32"),
+            ("2001:db8:0:0:0:0:0:0/32", 0..20, "2001:db8::/32"),
+            ("2001:db8::/032", 11..14, "2001:db8::/32"),
+            ("2001:db8::1/64", 0..11, "2001:db8::/64"),
+        ];
+
+        for (input, expected_span, expected_suggestion) in expected_rejections {
+            // Act
+            let actual_error = Cidr::parse_strict(input).unwrap_err();
+
+            // Assert
+            assert_eq!(actual_error.span(), expected_span, "{input}");
+            assert_eq!(actual_error.suggestion(), Some(expected_suggestion));
+        }
+    }
+
+    #[test]
+    fn test_parse_strict_keeps_parse_errors() {
+        // Arrange
+
+        // Act
+        let actual_error = Cidr::parse_strict("10.0.0.0/33").unwrap_err();
+
+        // Assert
+        assert!(matches!(actual_error, StrictParseError::Cidr(_)));
+        assert_eq!(actual_error.suggestion(), None);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::cidr::Cidr;
+use crate::ipset::IpSet;
+use crate::range::{block_size_minus_one, range_to_blocks};
+use std::collections::BTreeMap;
+
+/// How much of a pool its allocations use and how fragmented the rest is
+///
+/// Address counts saturate at `u128::MAX`, which only a whole IPv6 address space exceeds
+#[derive(Debug, PartialEq, Clone)]
+pub struct Utilization {
+    pub pool: Cidr,
+    pub total_addresses: u128,
+    /// Addresses of the pool covered by allocations, overlapping allocations counted once
+    pub used_addresses: u128,
+    pub free_addresses: u128,
+    /// Largest aligned free block, the lowest one if several have the same size
+    pub largest_free_block: Option<Cidr>,
+    /// Contiguous free ranges
+    pub free_fragments: usize,
+    /// Allocations not entirely inside the pool, in input order. Their part inside the pool still counts as used
+    pub outside: Vec<Cidr>,
+    /// Allocations inside the pool by prefix length
+    pub allocated_by_prefix: BTreeMap<u8, usize>,
+    /// Aligned free blocks by prefix length
+    pub free_by_prefix: BTreeMap<u8, usize>,
+}
+
+impl Utilization {
+    /// Used addresses as a percentage of the pool
+    pub fn percent(&self) -> f64 {
+        self.used_addresses as f64 / self.total_addresses as f64 * 100.0
+    }
+}
+
+fn address_count(ranges: &[(u128, u128)]) -> u128 {
+    ranges
+        .iter()
+        .map(|(start, end)| (end - start).saturating_add(1))
+        .fold(0, u128::saturating_add)
+}
+
+impl Cidr {
+    /// Utilization of the network as a pool holding `allocations`
+    ///
+    /// # Example
+    /// ```
+    /// use cidit::Cidr;
+    ///
+    /// let pool: Cidr = "10.0.0.0/22".parse().unwrap();
+    /// let allocations: Vec<Cidr> = vec!["10.0.0.0/24".parse().unwrap(), "10.0.2.0/24".parse().unwrap()];
+    ///
+    /// let actual_utilization = pool.utilization(&allocations);
+    ///
+    /// assert_eq!(actual_utilization.used_addresses, 512);
+    /// assert_eq!(actual_utilization.percent(), 50.0);
+    /// assert_eq!(actual_utilization.free_fragments, 2);
+    /// ```
+    pub fn utilization(&self, allocations: &[Cidr]) -> Utilization {
+        let bits = self.bits();
+        let (inside, outside): (Vec<Cidr>, Vec<Cidr>) = allocations
+            .iter()
+            .partition(|allocation| self.is_supernet_of(allocation));
+        let pool = IpSet::from(*self);
+        let allocated: IpSet = allocations.iter().copied().collect();
+        let free = pool.difference(&allocated);
+        let free_ranges = free.ranges(bits);
+        let free_blocks: Vec<(u128, u8)> = free_ranges
+            .iter()
+            .flat_map(|&(start, end)| range_to_blocks(start, end, bits))
+            .collect();
+
+        let mut allocated_by_prefix = BTreeMap::new();
+        for allocation in &inside {
+            *allocated_by_prefix
+                .entry(allocation.prefix_len())
+                .or_insert(0) += 1;
+        }
+        let mut free_by_prefix = BTreeMap::new();
+        for (_, prefix) in &free_blocks {
+            *free_by_prefix.entry(*prefix).or_insert(0) += 1;
+        }
+
+        Utilization {
+            pool: *self,
+            total_addresses: block_size_minus_one(bits - self.prefix_len()).saturating_add(1),
+            used_addresses: address_count(pool.intersection(&allocated).ranges(bits)),
+            free_addresses: address_count(free_ranges),
+            largest_free_block: free_blocks
+                .iter()
+                .min_by_key(|(start, prefix)| (*prefix, *start))
+                .map(|&(start, prefix)| Cidr::from_block(start, prefix, bits)),
+            free_fragments: free_ranges.len(),
+            outside,
+            allocated_by_prefix,
+            free_by_prefix,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cidr::{Cidr, cidrs};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_utilization() {
+        // Arrange
+        let expected_pool: Cidr = "10.0.0.0/16".parse().unwrap();
+        let expected_allocations = cidrs(&[
+            "10.0.0.0/24",
+            "10.0.0.128/25",
+            "10.0.2.0/23",
+            "192.168.0.0/24",
+            "2001:db8::/32",
+        ]);
+
+        // Act
+        let actual_utilization = expected_pool.utilization(&expected_allocations);
+
+        // Assert
+        assert_eq!(actual_utilization.total_addresses, 65536);
+        assert_eq!(actual_utilization.used_addresses, 768);
+        assert_eq!(actual_utilization.free_addresses, 64768);
+        assert_eq!(
+            actual_utilization.largest_free_block,
+            Some("10.0.128.0/17".parse().unwrap())
+        );
+        assert_eq!(actual_utilization.free_fragments, 2);
+        assert_eq!(
+            actual_utilization.outside,
+            cidrs(&["192.168.0.0/24", "2001:db8::/32"])
+        );
+        assert_eq!(
+            actual_utilization.allocated_by_prefix,
+            BTreeMap::from([(23, 1), (24, 1), (25, 1)])
+        );
+        assert_eq!(actual_utilization.free_by_prefix[&24], 1);
+        assert_eq!(actual_utilization.free_by_prefix[&17], 1);
+    }
+
+    #[test]
+    fn test_utilization_partly_outside() {
+        // Arrange
+        let expected_pool: Cidr = "10.0.0.0/24".parse().unwrap();
+        let expected_allocations = cidrs(&["10.0.0.0/23"]);
+
+        // Act
+        let actual_utilization = expected_pool.utilization(&expected_allocations);
+
+        // Assert
+        assert_eq!(actual_utilization.percent(), 100.0);
+        assert_eq!(actual_utilization.largest_free_block, None);
+        assert_eq!(actual_utilization.free_fragments, 0);
+        assert_eq!(actual_utilization.outside, expected_allocations);
+    }
+
+    #[test]
+    fn test_utilization_of_the_whole_ipv6_space() {
+        // Arrange
+        let expected_pool: Cidr = "::/0".parse().unwrap();
+
+        // Act
+        let actual_utilization = expected_pool.utilization(&[]);
+
+        // Assert
+        assert_eq!(actual_utilization.total_addresses, u128::MAX);
+        assert_eq!(actual_utilization.used_addresses, 0);
+        assert_eq!(actual_utilization.free_addresses, u128::MAX);
+        assert_eq!(actual_utilization.largest_free_block, Some(expected_pool));
+    }
+}
@@ -0,0 +1,315 @@
+use crate::cidr::Cidr;
+use crate::range::{IPV4_BITS, block_size_minus_one, range_to_blocks};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum VlsmRequestParseError {
+    #[error("Invalid subnet request format (expected name=hosts)")]
+    InvalidFormat,
+    #[error("Invalid host count: {0}")]
+    InvalidHosts(String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum VlsmError {
+    #[error("Subnet '{0}' must have at least one host")]
+    ZeroHosts(String),
+    #[error("Subnet '{name}' needs {hosts} hosts, which does not fit into {parent}")]
+    InsufficientSpace {
+        name: String,
+        hosts: u128,
+        parent: Cidr,
+    },
+}
+
+/// A named subnet that needs room for a number of hosts, e.g. `web=500`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VlsmRequest {
+    pub name: String,
+    pub hosts: u128,
+}
+
+impl FromStr for VlsmRequest {
+    type Err = VlsmRequestParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, hosts) = s
+            .split_once('=')
+            .ok_or(VlsmRequestParseError::InvalidFormat)?;
+        let hosts: u128 = hosts
+            .trim()
+            .parse()
+            .map_err(|_| VlsmRequestParseError::InvalidHosts(hosts.to_string()))?;
+        Ok(Self {
+            name: name.trim().to_string(),
+            hosts,
+        })
+    }
+}
+
+/// A subnet allocated for a [`VlsmRequest`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VlsmAllocation {
+    pub name: String,
+    pub hosts: u128,
+    pub cidr: Cidr,
+}
+
+/// Variable-length subnet plan of a parent network
+///
+/// # Example
+/// ```
+/// use cidit::{Cidr, VlsmPlan, VlsmRequest};
+///
+/// let expected_parent: Cidr = "10.0.0.0/22".parse().unwrap();
+/// let expected_requests: Vec<VlsmRequest> = vec!["db=60".parse().unwrap(), "web=500".parse().unwrap()];
+///
+/// let actual_plan = VlsmPlan::allocate(&expected_parent, &expected_requests).unwrap();
+///
+/// assert_eq!(actual_plan.allocations[0].name, "web");
+/// assert_eq!(actual_plan.allocations[0].cidr, "10.0.0.0/23".parse().unwrap());
+/// assert_eq!(actual_plan.allocations[1].cidr, "10.0.2.0/26".parse().unwrap());
+/// assert_eq!(actual_plan.free.len(), 3);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VlsmPlan {
+    /// Allocations, largest first
+    pub allocations: Vec<VlsmAllocation>,
+    /// Minimal list of networks left unallocated in the parent
+    pub free: Vec<Cidr>,
+}
+
+/// Host bits of the smallest network that has `hosts` usable addresses
+///
+/// IPv4 networks lose the network and broadcast addresses, except /31 and /32 (RFC 3021).
+/// Every address of an IPv6 network is usable
+fn host_bits_for(hosts: u128, bits: u8) -> Option<u8> {
+    match (bits, hosts) {
+        (IPV4_BITS, 1) => Some(0),
+        (IPV4_BITS, 2) => Some(1),
+        (IPV4_BITS, _) => (2..=bits).find(|&host_bits| block_size_minus_one(host_bits) > hosts),
+        _ => (0..=bits).find(|&host_bits| block_size_minus_one(host_bits) >= hosts - 1),
+    }
+}
+
+impl VlsmPlan {
+    /// Allocates requests largest-first, back to back from the start of the parent network
+    ///
+    /// Allocating in descending size keeps every subnet aligned to its own size
+    pub fn allocate(parent: &Cidr, requests: &[VlsmRequest]) -> Result<Self, VlsmError> {
+        let bits = parent.bits();
+        let (parent_first, parent_last) = parent.range();
+        let mut sized_requests: Vec<(u8, &VlsmRequest)> = requests
+            .iter()
+            .map(|request| match request.hosts {
+                0 => Err(VlsmError::ZeroHosts(request.name.clone())),
+                hosts => host_bits_for(hosts, bits)
+                    .map(|host_bits| (host_bits, request))
+                    .ok_or_else(|| VlsmError::InsufficientSpace {
+                        name: request.name.clone(),
+                        hosts,
+                        parent: *parent,
+                    }),
+            })
+            .collect::<Result<_, _>>()?;
+        sized_requests.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        let mut next: Option<u128> = Some(parent_first);
+        let mut allocations = Vec::with_capacity(sized_requests.len());
+        for (host_bits, request) in sized_requests {
+            let start = next
+                .filter(|&start| block_size_minus_one(host_bits) <= parent_last - start)
+                .ok_or_else(|| VlsmError::InsufficientSpace {
+                    name: request.name.clone(),
+                    hosts: request.hosts,
+                    parent: *parent,
+                })?;
+            let last = start + block_size_minus_one(host_bits);
+            allocations.push(VlsmAllocation {
+                name: request.name.clone(),
+                hosts: request.hosts,
+                cidr: Cidr::from_block(start, bits - host_bits, bits),
+            });
+            next = last.checked_add(1).filter(|&next| next <= parent_last);
+        }
+
+        let free = next
+            .map(|start| range_to_blocks(start, parent_last, bits))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(start, prefix)| Cidr::from_block(start, prefix, bits))
+            .collect();
+
+        Ok(Self { allocations, free })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VlsmError, VlsmPlan, VlsmRequest, VlsmRequestParseError, host_bits_for};
+    use crate::cidr::Cidr;
+
+    #[test]
+    fn test_parse_request() {
+        // Arrange
+        let expected_request = VlsmRequest {
+            name: "web".to_string(),
+            hosts: 500,
+        };
+
+        // Act
+        let actual_request: VlsmRequest = "web=500".parse().unwrap();
+
+        // Assert
+        assert_eq!(actual_request, expected_request);
+    }
+
+    #[test]
+    fn test_parse_request_invalid() {
+        // Arrange
+
+        // Act
+        let actual_no_name = "500".parse::<VlsmRequest>();
+        let actual_no_hosts = "web=many".parse::<VlsmRequest>();
+
+        // Assert
+        assert_eq!(actual_no_name, Err(VlsmRequestParseError::InvalidFormat));
+        assert_eq!(
+            actual_no_hosts,
+            Err(VlsmRequestParseError::InvalidHosts("many".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_host_bits_for_ipv4() {
+        // Arrange
+
+        // Act
+        let actual_host_bits: Vec<Option<u8>> = [1, 2, 3, 254, 255, u32::MAX.into()]
+            .into_iter()
+            .map(|hosts| host_bits_for(hosts, 32))
+            .collect();
+
+        // Assert
+        assert_eq!(
+            actual_host_bits,
+            vec![Some(0), Some(1), Some(3), Some(8), Some(9), None]
+        );
+    }
+
+    #[test]
+    fn test_host_bits_for_ipv6() {
+        // Arrange
+
+        // Act
+        let actual_host_bits: Vec<Option<u8>> = [1, 2, 3, 256, u128::MAX]
+            .into_iter()
+            .map(|hosts| host_bits_for(hosts, 128))
+            .collect();
+
+        // Assert
+        assert_eq!(
+            actual_host_bits,
+            vec![Some(0), Some(1), Some(2), Some(8), Some(128)]
+        );
+    }
+
+    #[test]
+    fn test_allocate_largest_first() {
+        // Arrange
+        let expected_parent: Cidr = "10.0.0.0/22".parse().unwrap();
+        let expected_allocations: Vec<(&str, Cidr)> = vec![
+            ("web", "10.0.0.0/23".parse().unwrap()),
+            ("db", "10.0.2.0/26".parse().unwrap()),
+            ("mgmt", "10.0.2.64/28".parse().unwrap()),
+            ("p2p", "10.0.2.80/31".parse().unwrap()),
+        ];
+        let expected_free: Vec<Cidr> = vec![
+            "10.0.2.82/31".parse().unwrap(),
+            "10.0.2.84/30".parse().unwrap(),
+            "10.0.2.88/29".parse().unwrap(),
+            "10.0.2.96/27".parse().unwrap(),
+            "10.0.2.128/25".parse().unwrap(),
+            "10.0.3.0/24".parse().unwrap(),
+        ];
+
+        // Act
+        let actual_plan = VlsmPlan::allocate(
+            &expected_parent,
+            &[
+                "mgmt=10".parse().unwrap(),
+                "p2p=2".parse().unwrap(),
+                "web=500".parse().unwrap(),
+                "db=60".parse().unwrap(),
+            ],
+        )
+        .unwrap();
+
+        // Assert
+        let actual_allocations: Vec<(&str, Cidr)> = actual_plan
+            .allocations
+            .iter()
+            .map(|allocation| (allocation.name.as_str(), allocation.cidr))
+            .collect();
+        assert_eq!(actual_allocations, expected_allocations);
+        assert_eq!(actual_plan.free, expected_free);
+    }
+
+    #[test]
+    fn test_allocate_exact_fit() {
+        // Arrange
+        let expected_parent: Cidr = "2001:db8::/63".parse().unwrap();
+
+        // Act
+        let actual_plan = VlsmPlan::allocate(
+            &expected_parent,
+            &[
+                "a=18446744073709551616".parse().unwrap(),
+                "b=2".parse().unwrap(),
+            ],
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(
+            actual_plan.allocations[1].cidr,
+            "2001:db8:0:1::/127".parse().unwrap()
+        );
+        assert_eq!(actual_plan.free.len(), 63);
+    }
+
+    #[test]
+    fn test_allocate_insufficient_space() {
+        // Arrange
+        let expected_parent: Cidr = "10.0.0.0/24".parse().unwrap();
+
+        // Act
+        let actual_result = VlsmPlan::allocate(
+            &expected_parent,
+            &["web=200".parse().unwrap(), "db=60".parse().unwrap()],
+        );
+
+        // Assert
+        assert_eq!(
+            actual_result,
+            Err(VlsmError::InsufficientSpace {
+                name: "db".to_string(),
+                hosts: 60,
+                parent: expected_parent,
+            })
+        );
+    }
+
+    #[test]
+    fn test_allocate_zero_hosts() {
+        // Arrange
+        let expected_parent: Cidr = "10.0.0.0/24".parse().unwrap();
+
+        // Act
+        let actual_result = VlsmPlan::allocate(&expected_parent, &["web=0".parse().unwrap()]);
+
+        // Assert
+        assert_eq!(actual_result, Err(VlsmError::ZeroHosts("web".to_string())));
+    }
+}
@@ -0,0 +1,126 @@
+//! Skeleton BIND zone files for reverse DNS zones
+
+use cidit::{Cidr, ReverseZone};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Zones with at most this many addresses get a PTR placeholder per address, wider ones a commented example
+const MAX_PTR_PLACEHOLDERS: u128 = 256;
+
+/// First address, number of addresses (`None` if it does not fit a u128) and bits of a network
+fn bounds(cidr: &Cidr) -> (u128, Option<u128>, u32) {
+    let (start, bits) = match cidr {
+        Cidr::V4(v4) => (u128::from(u32::from(v4.network())), 32),
+        Cidr::V6(v6) => (u128::from(v6.network()), 128),
+    };
+    let size = 1u128.checked_shl(bits - u32::from(cidr.prefix_len()));
+    (start, size, bits)
+}
+
+fn address(value: u128, bits: u32) -> IpAddr {
+    match bits {
+        32 => IpAddr::V4(Ipv4Addr::from(value as u32)),
+        _ => IpAddr::V6(Ipv6Addr::from(value)),
+    }
+}
+
+/// PTR record of `addr` pointing at a placeholder host name
+fn ptr_record(zone: &ReverseZone, addr: IpAddr) -> String {
+    let host = addr.to_string().replace(['.', ':'], "-");
+    format!(
+        "{}\tIN\tPTR\thost-{}.example.com.",
+        zone.relative_name(addr).unwrap_or_default(),
+        host
+    )
+}
+
+/// Zone file for `zone`, with PTR placeholders for the usable addresses of `cidr` it covers.
+/// RFC 2317 zones end with the CNAMEs their parent zone needs, as comments
+pub(crate) fn render(zone: &ReverseZone, cidr: &Cidr) -> Vec<String> {
+    let mut lines = vec![
+        format!("$ORIGIN {}.", zone.name),
+        "$TTL 3600".to_string(),
+        "@\tIN\tSOA\tns1.example.com. hostmaster.example.com. ( 1 3600 900 604800 3600 )"
+            .to_string(),
+        "@\tIN\tNS\tns1.example.com.".to_string(),
+    ];
+
+    // Network and broadcast addresses of IPv4 networks wider than /31 get no PTR record
+    let (cidr_start, cidr_size, _) = bounds(cidr);
+    let reserved = |value: u128| match (cidr, cidr_size) {
+        (Cidr::V4(_), Some(size)) if size > 2 => {
+            value == cidr_start || value == cidr_start + size - 1
+        }
+        _ => false,
+    };
+    let (start, size, bits) = bounds(&zone.network);
+    let addresses: Vec<IpAddr> = match size {
+        Some(size) if size <= MAX_PTR_PLACEHOLDERS => (start..start + size)
+            .filter(|value| !reserved(*value))
+            .map(|value| address(value, bits))
+            .collect(),
+        _ => {
+            let example = address(start + 1, bits);
+            lines.push(format!("; {}", ptr_record(zone, example)));
+            Vec::new()
+        }
+    };
+    lines.extend(addresses.iter().map(|addr| ptr_record(zone, *addr)));
+
+    if let Some(parent) = &zone.parent {
+        let delegation = zone
+            .name
+            .strip_suffix(&format!(".{parent}"))
+            .unwrap_or_default();
+        lines.push(format!("; Records to add to the {parent} zone:"));
+        lines.push(format!("; {delegation}\tIN\tNS\tns1.example.com."));
+        lines.extend(addresses.iter().map(|addr| {
+            let name = zone.relative_name(*addr).unwrap_or_default();
+            format!("; {name}\tIN\tCNAME\t{name}.{}.", zone.name)
+        }));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use cidit::Cidr;
+
+    #[test]
+    fn test_render_classless_delegation() {
+        // Arrange
+        let expected_cidr: Cidr = "192.0.2.64/30".parse().unwrap();
+        let expected_lines = vec![
+            "$ORIGIN 64/30.2.0.192.in-addr.arpa.",
+            "$TTL 3600",
+            "@\tIN\tSOA\tns1.example.com. hostmaster.example.com. ( 1 3600 900 604800 3600 )",
+            "@\tIN\tNS\tns1.example.com.",
+            "65\tIN\tPTR\thost-192-0-2-65.example.com.",
+            "66\tIN\tPTR\thost-192-0-2-66.example.com.",
+            "; Records to add to the 2.0.192.in-addr.arpa zone:",
+            "; 64/30\tIN\tNS\tns1.example.com.",
+            "; 65\tIN\tCNAME\t65.64/30.2.0.192.in-addr.arpa.",
+            "; 66\tIN\tCNAME\t66.64/30.2.0.192.in-addr.arpa.",
+        ];
+
+        // Act
+        let actual_lines = render(&expected_cidr.reverse_zones()[0], &expected_cidr);
+
+        // Assert
+        assert_eq!(actual_lines, expected_lines);
+    }
+
+    #[test]
+    fn test_render_wide_zone_has_an_example() {
+        // Arrange
+        let expected_cidr: Cidr = "2001:db8::/32".parse().unwrap();
+        let expected_example = "; 1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0\tIN\tPTR\thost-2001-db8--1.example.com.";
+
+        // Act
+        let actual_lines = render(&expected_cidr.reverse_zones()[0], &expected_cidr);
+
+        // Assert
+        assert_eq!(actual_lines.len(), 5);
+        assert_eq!(actual_lines[4], expected_example);
+    }
+}